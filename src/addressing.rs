@@ -0,0 +1,930 @@
+//! LoRaWAN NetID and Devaddr addressing.
+//!
+//! Nothing in here knows about the UI, so it can be used from other crates
+//! with `default-features = false`.
+use std::{fmt, str::FromStr};
+
+/// Width of the NwkID portion of a Devaddr, indexed by type.
+const NWK_ID_BITS: [usize; 8] = [6, 6, 9, 11, 12, 13, 15, 17];
+/// Width of the ID field of a NetID, indexed by type. Types 0-2 leave the
+/// rest of the 21 bits as RFU, types 3-7 only put the bottom
+/// [`NWK_ID_BITS`] of it into a Devaddr.
+const ID_BITS: [usize; 8] = [6, 6, 9, 21, 21, 21, 21, 21];
+/// Width of the NwkAddr portion of a Devaddr, indexed by type.
+const ADDR_BITS: [usize; 8] = [25, 24, 20, 17, 15, 13, 10, 7];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Devaddr {
+    mem_type: u8,
+    nwk_addr: u32,
+    addr: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetID {
+    mem_type: u8,
+    id: u32,
+}
+
+/// An inclusive range of Devaddrs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DevaddrRange {
+    start: u32,
+    end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DevAddrError {
+    Empty,
+    /// Input contained something other than hex (or decimal) digits.
+    NotHex(char),
+    /// More than 8 hex digits that also don't make a decimal `u32`.
+    WrongLength(usize),
+    /// The Devaddr starts with 8 ones, which is not a valid type prefix.
+    InvalidPrefix(u32),
+    InvalidMemType(u8),
+    /// The NwkID doesn't fit in the bits this type has for it.
+    NwkIdOutOfRange {
+        mem_type: u8,
+        id: u32,
+    },
+    /// The address index is past the end of the NetID's range.
+    AddrOutOfRange {
+        addr: u32,
+        size: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetIdError {
+    Empty,
+    /// Input contained something other than hex digits.
+    NotHex(char),
+    /// More than 6 hex digits.
+    WrongLength(usize),
+    /// NetIDs are only 24 bits wide.
+    OutOfRange(u32),
+    InvalidMemType(u8),
+    /// The NwkID doesn't fit in the bits this type has for it.
+    NwkIdOutOfRange {
+        mem_type: u8,
+        id: u32,
+    },
+    /// Types 0-2 only use the bottom of the ID, the rest must be zero.
+    RfuBitsSet {
+        mem_type: u8,
+        id: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeError {
+    Empty,
+    /// One end contained something other than hex digits.
+    NotHex(char),
+    /// One end had more than 8 hex digits.
+    WrongLength(usize),
+    /// The `n` in `start/n` wasn't a number from 0 to 32.
+    InvalidPrefixLen(String),
+    /// `start/n` runs past `FFFFFFFF`.
+    PastEnd {
+        start: u32,
+        prefix_len: u32,
+    },
+    Backwards {
+        start: u32,
+        end: u32,
+    },
+}
+
+/// Problems shared by anything parsed out of a hex string.
+enum HexError {
+    Empty,
+    NotHex(char),
+    WrongLength(usize),
+}
+
+impl Devaddr {
+    /// Parse a Devaddr from hex, falling back to decimal for inputs too long
+    /// to be hex.
+    pub fn new(input: &str) -> Result<Self, DevAddrError> {
+        let input = input.trim();
+        let devaddr = match parse_hex(input, 8) {
+            Ok(devaddr) => devaddr,
+            Err(HexError::WrongLength(len)) => {
+                u32::from_str(input).map_err(|_| DevAddrError::WrongLength(len))?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::try_from(devaddr)
+    }
+
+    /// Build a Devaddr from its parts. Bits of `addr` that don't fit in the
+    /// type are dropped.
+    pub fn with_fields(mem_type: u8, id: u32, addr: u32) -> Result<Self, DevAddrError> {
+        let nwk_id_bits =
+            nwk_id_offset_for_mem_type(mem_type).ok_or(DevAddrError::InvalidMemType(mem_type))?;
+        if id >> nwk_id_bits != 0 {
+            return Err(DevAddrError::NwkIdOutOfRange { mem_type, id });
+        }
+
+        let addr_bits_to_shift = ADDR_BITS[mem_type as usize];
+        Ok(Self {
+            mem_type,
+            nwk_addr: id,
+            addr: new_num_from(addr, (32 - addr_bits_to_shift)..=32),
+        })
+    }
+
+    pub fn mem_type(&self) -> u8 {
+        self.mem_type
+    }
+
+    pub fn nwk_addr(&self) -> u32 {
+        self.nwk_addr
+    }
+
+    /// Width of the NwkID portion of this Devaddr.
+    pub fn nwk_id_bits(&self) -> usize {
+        NWK_ID_BITS[self.mem_type as usize]
+    }
+
+    /// Index of this Devaddr within its NetID's range, starting at 0.
+    pub fn addr(&self) -> u32 {
+        self.addr
+    }
+
+    pub fn num(&self) -> u32 {
+        // `mem_type` ones followed by a zero.
+        let mem_type_bits = !(u32::MAX >> self.mem_type);
+
+        let addr_bits_to_shift = ADDR_BITS[self.mem_type as usize];
+
+        let nwk_id_bits = self.nwk_addr << addr_bits_to_shift;
+        let addr_bits = new_num_from(self.addr, (32 - addr_bits_to_shift)..=32);
+        mem_type_bits | nwk_id_bits | addr_bits
+    }
+
+    /// The NetID this Devaddr belongs to. For types 3-7 only the bits of the
+    /// NetID that make it into a Devaddr are known.
+    pub fn net_id(&self) -> NetID {
+        NetID {
+            mem_type: self.mem_type,
+            id: self.nwk_addr,
+        }
+    }
+
+    pub fn as_hex(&self) -> String {
+        format!("{:08X}", self.num())
+    }
+
+    pub fn as_dec(&self) -> String {
+        self.num().to_string()
+    }
+
+    pub fn as_arr(&self) -> String {
+        format!("{:?}", self.num().to_be_bytes())
+    }
+}
+
+impl NetID {
+    /// Parse a NetID from hex.
+    pub fn new(input: &str) -> Result<Self, NetIdError> {
+        let net_id = parse_hex(input.trim(), 6)?;
+        Self::try_from(net_id)
+    }
+
+    pub fn with_fields(mem_type: u8, id: u32) -> Result<Self, NetIdError> {
+        let id_bits = ID_BITS
+            .get(mem_type as usize)
+            .ok_or(NetIdError::InvalidMemType(mem_type))?;
+        if id >> id_bits != 0 {
+            return Err(NetIdError::NwkIdOutOfRange { mem_type, id });
+        }
+        Ok(Self { mem_type, id })
+    }
+
+    pub fn mem_type(&self) -> u8 {
+        self.mem_type
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn num(&self) -> u32 {
+        let leading = (self.mem_type as u32) << 21;
+        leading | self.id
+    }
+
+    /// Width of the ID field for this type.
+    pub fn id_bits(&self) -> usize {
+        ID_BITS[self.mem_type as usize]
+    }
+
+    /// Largest ID this type can hold.
+    pub fn max_id(&self) -> u32 {
+        (1 << self.id_bits()) - 1
+    }
+
+    /// Width of the part of the ID that ends up in a Devaddr.
+    pub fn nwk_id_bits(&self) -> usize {
+        NWK_ID_BITS[self.mem_type as usize]
+    }
+
+    /// The part of the ID that ends up in a Devaddr.
+    pub fn nwk_id(&self) -> u32 {
+        new_num_from(self.id, (32 - self.nwk_id_bits())..=32)
+    }
+
+    pub fn start_addr(&self) -> Devaddr {
+        self.devaddr(u32::MIN)
+    }
+
+    pub fn end_addr(&self) -> Devaddr {
+        self.devaddr(u32::MAX)
+    }
+
+    /// The Devaddr at index `addr` of this NetID's range.
+    pub fn devaddr_at(&self, addr: u32) -> Result<Devaddr, DevAddrError> {
+        let size = self.size();
+        if addr >= size {
+            return Err(DevAddrError::AddrOutOfRange { addr, size });
+        }
+        Ok(self.devaddr(addr))
+    }
+
+    fn devaddr(&self, addr: u32) -> Devaddr {
+        let addr_bits = self.addr_bits();
+        Devaddr {
+            mem_type: self.mem_type,
+            nwk_addr: self.nwk_id(),
+            addr: new_num_from(addr, (32 - addr_bits)..=32),
+        }
+    }
+
+    /// Number of bits a Devaddr in this NetID has left for the device address.
+    pub fn addr_bits(&self) -> usize {
+        ADDR_BITS[self.mem_type as usize]
+    }
+
+    /// Every Devaddr this NetID can hand out.
+    pub fn range(&self) -> DevaddrRange {
+        DevaddrRange {
+            start: self.start_addr().num(),
+            end: self.end_addr().num(),
+        }
+    }
+
+    /// Whether `devaddr` was handed out from this NetID's range.
+    pub fn contains(&self, devaddr: &Devaddr) -> bool {
+        (self.start_addr().num()..=self.end_addr().num()).contains(&devaddr.num())
+    }
+
+    /// Number of Devaddrs available to this NetID.
+    pub fn size(&self) -> u32 {
+        self.end_addr().addr - self.start_addr().addr + 1
+    }
+
+    pub fn as_hex(&self) -> String {
+        format!("{:06X}", self.num())
+    }
+
+    pub fn as_dec(&self) -> String {
+        self.num().to_string()
+    }
+
+    pub fn as_arr(&self) -> String {
+        let arr: [u8; 4] = self.num().to_be_bytes();
+        format!("[{}, {}, {}]", arr[1], arr[2], arr[3])
+    }
+}
+
+impl DevaddrRange {
+    pub fn new(start: u32, end: u32) -> Result<Self, RangeError> {
+        if start > end {
+            return Err(RangeError::Backwards { start, end });
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    /// Number of Devaddrs in the range.
+    pub fn size(&self) -> u64 {
+        (self.end - self.start) as u64 + 1
+    }
+
+    pub fn contains(&self, num: u32) -> bool {
+        (self.start..=self.end).contains(&num)
+    }
+
+    pub fn overlaps(&self, other: &DevaddrRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The `/n` this range can be written as, if it's a power of two in size
+    /// and starts on a multiple of that size.
+    pub fn prefix_len(&self) -> Option<u32> {
+        let size = self.size();
+        let aligned = size.is_power_of_two() && self.start as u64 % size == 0;
+        aligned.then(|| 32 - size.trailing_zeros())
+    }
+
+    /// The part of this range that's also in `other`.
+    pub fn intersection(&self, other: &DevaddrRange) -> Option<DevaddrRange> {
+        self.overlaps(other).then(|| DevaddrRange {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// The `prefix_len` sized block starting at `start`. Nothing checks that
+    /// `start` is aligned, use [`DevaddrRange::prefix_len`] for that.
+    pub fn from_prefix(start: u32, prefix_len: u32) -> Result<Self, RangeError> {
+        let size = block_size(prefix_len)?;
+        let end = start as u64 + size - 1;
+        if end > u32::MAX as u64 {
+            return Err(RangeError::PastEnd { start, prefix_len });
+        }
+        DevaddrRange::new(start, end as u32)
+    }
+
+    pub fn contains_range(&self, other: &DevaddrRange) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Cut the range into `prefix_len` sized pieces, the last one is cut
+    /// short if the range doesn't divide evenly.
+    pub fn split(&self, prefix_len: u32) -> Result<impl Iterator<Item = DevaddrRange>, RangeError> {
+        let size = block_size(prefix_len)?;
+        let (start, end) = (self.start as u64, self.end as u64);
+        // Counted in u64, a /0 block doesn't fit a 32 bit usize.
+        let pieces = (end - start) / size + 1;
+        Ok((0..pieces).map(move |i| {
+            let start = start + i * size;
+            DevaddrRange {
+                start: start as u32,
+                end: (start + size - 1).min(end) as u32,
+            }
+        }))
+    }
+
+    /// The fewest prefix aligned blocks that cover exactly this range.
+    pub fn to_prefixes(&self) -> Vec<DevaddrRange> {
+        let mut prefixes = vec![];
+        let mut start = self.start as u64;
+        let end = self.end as u64;
+        while start <= end {
+            // Biggest block `start` is aligned to, shrunk until it fits.
+            let mut size = 1u64 << start.trailing_zeros().min(32);
+            while start + size - 1 > end {
+                size >>= 1;
+            }
+            prefixes.push(DevaddrRange {
+                start: start as u32,
+                end: (start + size - 1) as u32,
+            });
+            start += size;
+        }
+        prefixes
+    }
+
+    /// Combine overlapping and touching ranges.
+    pub fn merge(ranges: impl IntoIterator<Item = DevaddrRange>) -> Vec<DevaddrRange> {
+        let mut ranges: Vec<DevaddrRange> = ranges.into_iter().collect();
+        ranges.sort();
+
+        let mut merged: Vec<DevaddrRange> = vec![];
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start as u64 <= last.end as u64 + 1 => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    /// How many NetIDs [`DevaddrRange::net_ids`] gives, without walking them.
+    pub fn net_id_count(&self) -> u64 {
+        (0..ADDR_BITS.len())
+            .filter_map(|mem_type| {
+                // Every Devaddr of this type, `mem_type` ones then a zero.
+                let type_start = !(u32::MAX >> mem_type);
+                let type_range =
+                    DevaddrRange::new(type_start, type_start | u32::MAX >> (mem_type + 1)).ok()?;
+                let part = self.intersection(&type_range)?;
+                let shift = ADDR_BITS[mem_type];
+                Some(((part.end >> shift) - (part.start >> shift)) as u64 + 1)
+            })
+            .sum()
+    }
+
+    /// Every NetID with at least one Devaddr in this range, in order.
+    pub fn net_ids(&self) -> impl Iterator<Item = NetID> {
+        let end = self.end;
+        let mut next = Some(self.start);
+        std::iter::from_fn(move || {
+            let current = next.filter(|num| *num <= end)?;
+            // Devaddrs starting with 8 ones don't belong to any NetID, and
+            // they're at the very end so there's nothing after them.
+            let net_id = Devaddr::try_from(current).ok()?.net_id();
+            next = net_id.end_addr().num().checked_add(1);
+            Some(net_id)
+        })
+    }
+}
+
+fn block_size(prefix_len: u32) -> Result<u64, RangeError> {
+    if prefix_len > 32 {
+        return Err(RangeError::InvalidPrefixLen(prefix_len.to_string()));
+    }
+    Ok(1 << (32 - prefix_len))
+}
+
+/// Pull the bits covered by `range` out of `num`.
+///
+/// `range` counts from the most significant bit, so `0..=8` is the first byte.
+pub fn new_num_from(num: u32, range: std::ops::RangeInclusive<usize>) -> u32 {
+    let end_bit = 32 - range.start() - 1;
+    let start_bit = 32 - range.end(); // inclusive, don't subtract 1
+
+    // Create a mask with ones in the specified range of bits.
+    let mask = ((1 << (end_bit - start_bit + 1)) - 1) << start_bit;
+
+    // Use the mask to extract the desired bits from num.
+    (num & mask) >> start_bit
+}
+
+/// Width of the NwkID portion of a Devaddr for each type, `None` above 7.
+pub fn nwk_id_offset_for_mem_type(mem_type: u8) -> Option<usize> {
+    NWK_ID_BITS.get(mem_type as usize).copied()
+}
+
+/// Width of the NwkAddr portion of a Devaddr for each type, `None` above 7.
+pub fn addr_offset_for_mem_type(mem_type: u8) -> Option<usize> {
+    ADDR_BITS.get(mem_type as usize).copied()
+}
+
+fn parse_hex(input: &str, max_digits: usize) -> Result<u32, HexError> {
+    if input.is_empty() {
+        return Err(HexError::Empty);
+    }
+    if let Some(ch) = input.chars().find(|ch| !ch.is_ascii_hexdigit()) {
+        return Err(HexError::NotHex(ch));
+    }
+    if input.len() > max_digits {
+        return Err(HexError::WrongLength(input.len()));
+    }
+    // Only hex digits and not too many of them, this can't fail.
+    Ok(u32::from_str_radix(input, 16).unwrap_or_default())
+}
+
+impl TryFrom<u32> for Devaddr {
+    type Error = DevAddrError;
+
+    fn try_from(devaddr: u32) -> Result<Self, Self::Error> {
+        let mem_type = devaddr.leading_ones() as u8;
+        if mem_type > 7 {
+            return Err(DevAddrError::InvalidPrefix(devaddr));
+        }
+
+        let nwk_addr_start = mem_type as usize + 1;
+        let nwk_addr_end = nwk_addr_start + NWK_ID_BITS[mem_type as usize];
+
+        let addr_start = 32 - ADDR_BITS[mem_type as usize];
+        let addr_end = 32;
+
+        Ok(Self {
+            mem_type,
+            nwk_addr: new_num_from(devaddr, nwk_addr_start..=nwk_addr_end),
+            addr: new_num_from(devaddr, addr_start..=addr_end),
+        })
+    }
+}
+
+impl TryFrom<u32> for NetID {
+    type Error = NetIdError;
+
+    fn try_from(net_id: u32) -> Result<Self, Self::Error> {
+        if net_id > 0xFF_FFFF {
+            return Err(NetIdError::OutOfRange(net_id));
+        }
+        let mem_type = new_num_from(net_id, 8..=11) as u8;
+        let id = new_num_from(net_id, 11..=32);
+        if id >> ID_BITS[mem_type as usize] != 0 {
+            return Err(NetIdError::RfuBitsSet { mem_type, id });
+        }
+        Ok(Self { mem_type, id })
+    }
+}
+
+impl FromStr for DevaddrRange {
+    type Err = RangeError;
+
+    /// Parse `start-end` or `start/prefix_len` with hex addresses. A single
+    /// address is a range of one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((start, prefix_len)) = s.split_once('/') {
+            let start = parse_hex(start.trim(), 8)?;
+            let prefix_len = prefix_len.trim();
+            let prefix_len = prefix_len
+                .parse()
+                .map_err(|_| RangeError::InvalidPrefixLen(prefix_len.to_string()))?;
+            return DevaddrRange::from_prefix(start, prefix_len);
+        }
+        match s.split_once('-') {
+            Some((start, end)) => {
+                DevaddrRange::new(parse_hex(start.trim(), 8)?, parse_hex(end.trim(), 8)?)
+            }
+            None => {
+                let num = parse_hex(s, 8)?;
+                DevaddrRange::new(num, num)
+            }
+        }
+    }
+}
+
+impl FromStr for Devaddr {
+    type Err = DevAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Devaddr::new(s)
+    }
+}
+
+impl FromStr for NetID {
+    type Err = NetIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NetID::new(s)
+    }
+}
+
+impl fmt::Display for Devaddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_hex())
+    }
+}
+
+impl fmt::Display for NetID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_hex())
+    }
+}
+
+impl fmt::Display for DevaddrRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08X}-{:08X}", self.start, self.end)
+    }
+}
+
+impl fmt::Display for DevAddrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty Devaddr"),
+            Self::NotHex(ch) => write!(f, "{ch:?} is not a hex digit"),
+            Self::WrongLength(len) => write!(f, "expected at most 8 hex digits, got {len}"),
+            Self::InvalidPrefix(devaddr) => write!(
+                f,
+                "{devaddr:08X} starts with 8 ones, which is not a valid type prefix"
+            ),
+            Self::InvalidMemType(mem_type) => write!(f, "type {mem_type} is not between 0 and 7"),
+            Self::NwkIdOutOfRange { mem_type, id } => match NWK_ID_BITS.get(*mem_type as usize) {
+                Some(bits) => write!(
+                    f,
+                    "NwkID {id:X} does not fit in the {bits} bits of a type {mem_type} Devaddr"
+                ),
+                None => write!(f, "type {mem_type} is not between 0 and 7"),
+            },
+            Self::AddrOutOfRange { addr, size: 0 } => {
+                write!(
+                    f,
+                    "address index {addr} is out of range, the NetID has no Devaddrs"
+                )
+            }
+            Self::AddrOutOfRange { addr, size } => write!(
+                f,
+                "address index {addr} is out of range, the NetID has {size} Devaddrs (0 to {})",
+                size - 1
+            ),
+        }
+    }
+}
+
+impl fmt::Display for NetIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty NetID"),
+            Self::NotHex(ch) => write!(f, "{ch:?} is not a hex digit"),
+            Self::WrongLength(len) => write!(f, "expected at most 6 hex digits, got {len}"),
+            Self::OutOfRange(net_id) => write!(f, "{net_id:X} is wider than 24 bits"),
+            Self::InvalidMemType(mem_type) => write!(f, "type {mem_type} is not between 0 and 7"),
+            Self::NwkIdOutOfRange { mem_type, id } => match ID_BITS.get(*mem_type as usize) {
+                Some(bits) => write!(
+                    f,
+                    "NwkID {id:X} does not fit in the {bits} bits of a type {mem_type} NetID"
+                ),
+                None => write!(f, "type {mem_type} is not between 0 and 7"),
+            },
+            Self::RfuBitsSet { mem_type, id } => match ID_BITS.get(*mem_type as usize) {
+                Some(bits) => write!(
+                    f,
+                    "RFU bits must be zero, type {mem_type} NetIDs only use the low {bits} bits of {id:X}"
+                ),
+                None => write!(f, "type {mem_type} is not between 0 and 7"),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty range"),
+            Self::NotHex(ch) => write!(f, "{ch:?} is not a hex digit"),
+            Self::WrongLength(len) => write!(f, "expected at most 8 hex digits, got {len}"),
+            Self::InvalidPrefixLen(prefix_len) => {
+                write!(f, "prefix length {prefix_len:?} is not between 0 and 32")
+            }
+            Self::PastEnd { start, prefix_len } => {
+                write!(f, "{start:08X}/{prefix_len} runs past FFFFFFFF")
+            }
+            Self::Backwards { start, end } => {
+                write!(f, "start {start:08X} is after end {end:08X}")
+            }
+        }
+    }
+}
+
+impl From<HexError> for DevAddrError {
+    fn from(e: HexError) -> Self {
+        match e {
+            HexError::Empty => Self::Empty,
+            HexError::NotHex(ch) => Self::NotHex(ch),
+            HexError::WrongLength(len) => Self::WrongLength(len),
+        }
+    }
+}
+
+impl From<HexError> for NetIdError {
+    fn from(e: HexError) -> Self {
+        match e {
+            HexError::Empty => Self::Empty,
+            HexError::NotHex(ch) => Self::NotHex(ch),
+            HexError::WrongLength(len) => Self::WrongLength(len),
+        }
+    }
+}
+
+impl From<HexError> for RangeError {
+    fn from(e: HexError) -> Self {
+        match e {
+            HexError::Empty => Self::Empty,
+            HexError::NotHex(ch) => Self::NotHex(ch),
+            HexError::WrongLength(len) => Self::WrongLength(len),
+        }
+    }
+}
+
+impl std::error::Error for DevAddrError {}
+impl std::error::Error for NetIdError {}
+impl std::error::Error for RangeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devaddr_round_trip() {
+        let d: Devaddr = "48000123".parse().unwrap();
+        assert_eq!(d.mem_type(), 0);
+        assert_eq!(d.nwk_addr(), 0x24);
+        assert_eq!(d.addr(), 0x123);
+        assert_eq!(d.to_string(), "48000123");
+        assert_eq!(d.net_id(), NetID::new("000024").unwrap());
+    }
+
+    #[test]
+    fn net_id_range() {
+        let n = NetID::try_from(0xC00053).unwrap();
+        assert_eq!(n.mem_type(), 6);
+        assert_eq!(n.id(), 0x53);
+        assert_eq!(n.start_addr().to_string(), "FC014C00");
+        assert_eq!(n.end_addr().to_string(), "FC014FFF");
+        assert_eq!(n.size(), 1024);
+        assert!(n.contains(&Devaddr::new("FC014C42").unwrap()));
+        assert!(!n.contains(&Devaddr::new("FC015000").unwrap()));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(
+            Devaddr::try_from(0xFF00_0000),
+            Err(DevAddrError::InvalidPrefix(_))
+        ));
+        assert!(matches!(
+            NetID::try_from(0x0100_0000),
+            Err(NetIdError::OutOfRange(_))
+        ));
+        assert_eq!("xyz".parse::<NetID>(), Err(NetIdError::NotHex('x')));
+        assert_eq!("".parse::<Devaddr>(), Err(DevAddrError::Empty));
+        assert_eq!(
+            "FF00000000".parse::<Devaddr>(),
+            Err(DevAddrError::WrongLength(10))
+        );
+        assert_eq!(
+            NetID::new("000400"),
+            Err(NetIdError::RfuBitsSet {
+                mem_type: 0,
+                id: 0x400
+            })
+        );
+        assert_eq!(
+            Devaddr::with_fields(8, 0, 0),
+            Err(DevAddrError::InvalidMemType(8))
+        );
+        assert_eq!(
+            Devaddr::with_fields(0, 64, 0),
+            Err(DevAddrError::NwkIdOutOfRange {
+                mem_type: 0,
+                id: 64
+            })
+        );
+
+        // Errors built by hand still format.
+        let odd = DevAddrError::NwkIdOutOfRange {
+            mem_type: 200,
+            id: 1,
+        };
+        assert_eq!(odd.to_string(), "type 200 is not between 0 and 7");
+        let empty = DevAddrError::AddrOutOfRange { addr: 0, size: 0 };
+        assert!(empty.to_string().contains("no Devaddrs"));
+        for odd in [
+            NetIdError::NwkIdOutOfRange { mem_type: 8, id: 1 },
+            NetIdError::RfuBitsSet {
+                mem_type: 255,
+                id: 1,
+            },
+        ] {
+            assert!(odd.to_string().contains("not between 0 and 7"));
+        }
+    }
+
+    #[test]
+    fn id_width_per_type() {
+        for (mem_type, nwk_id_bits) in NWK_ID_BITS.iter().enumerate() {
+            let n = NetID::with_fields(mem_type as u8, 0).unwrap();
+            assert_eq!(n.nwk_id_bits(), *nwk_id_bits);
+            assert!(NetID::with_fields(mem_type as u8, n.max_id()).is_ok());
+            assert!(NetID::with_fields(mem_type as u8, n.max_id() + 1).is_err());
+        }
+
+        // Only the bottom 11 bits of a type 3 ID make it into a Devaddr.
+        let n = NetID::with_fields(3, 0x1F_FFFF).unwrap();
+        assert_eq!(n.nwk_id(), 0x7FF);
+        assert_eq!(n.start_addr().to_string(), "EFFE0000");
+        assert_eq!(n.start_addr().net_id().nwk_id(), n.nwk_id());
+
+        assert_eq!(
+            NetID::new("400200"),
+            Err(NetIdError::RfuBitsSet {
+                mem_type: 2,
+                id: 0x200
+            })
+        );
+
+        // Bit 20 is the top of the 21 bit ID, not part of the type.
+        for hex in ["7FFFFF", "600000", "700000", "F00000"] {
+            assert_eq!(NetID::new(hex).unwrap().to_string(), hex);
+        }
+        assert_eq!(NetID::new("7FFFFF").unwrap().id(), 0x1F_FFFF);
+        assert_eq!(NetID::new("700000").unwrap().id(), 0x10_0000);
+        assert_eq!(
+            NetID::new("100000"),
+            Err(NetIdError::RfuBitsSet {
+                mem_type: 0,
+                id: 0x10_0000
+            })
+        );
+        assert_eq!(
+            NetID::new("300000"),
+            Err(NetIdError::RfuBitsSet {
+                mem_type: 1,
+                id: 0x10_0000
+            })
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        let a: DevaddrRange = "78000000-780000FF".parse().unwrap();
+        let b: DevaddrRange = "780000F0 - 7800010F".parse().unwrap();
+        assert_eq!(a.size(), 256);
+        assert_eq!(a.prefix_len(), Some(24));
+        assert_eq!(b.prefix_len(), None);
+        assert!(a.overlaps(&b));
+        assert_eq!(a.intersection(&b).unwrap().to_string(), "780000F0-780000FF");
+        assert_eq!(
+            NetID::new("00003C").unwrap().range().to_string(),
+            "78000000-79FFFFFF"
+        );
+        assert_eq!(
+            "2-1".parse::<DevaddrRange>(),
+            Err(RangeError::Backwards { start: 2, end: 1 })
+        );
+    }
+
+    #[test]
+    fn subnets() {
+        let r: DevaddrRange = "48000000/25".parse().unwrap();
+        assert_eq!(r.to_string(), "48000000-4800007F");
+        assert_eq!(r.prefix_len(), Some(25));
+        assert!(r.contains_range(&"48000010-4800001F".parse().unwrap()));
+        assert_eq!(
+            "FFFFFFFF/31".parse::<DevaddrRange>(),
+            Err(RangeError::PastEnd {
+                start: 0xFFFF_FFFF,
+                prefix_len: 31
+            })
+        );
+
+        let halves: Vec<_> = r.split(26).unwrap().map(|r| r.to_string()).collect();
+        assert_eq!(halves, ["48000000-4800003F", "48000040-4800007F"]);
+
+        let unaligned: DevaddrRange = "48000001-48000010".parse().unwrap();
+        let prefixes: Vec<_> = unaligned
+            .to_prefixes()
+            .iter()
+            .map(|r| (r.start(), r.prefix_len().unwrap()))
+            .collect();
+        assert_eq!(
+            prefixes,
+            [
+                (0x4800_0001, 32),
+                (0x4800_0002, 31),
+                (0x4800_0004, 30),
+                (0x4800_0008, 29),
+                (0x4800_0010, 32)
+            ]
+        );
+
+        let merged = DevaddrRange::merge([
+            "48000040-4800007F".parse().unwrap(),
+            "48000000-4800003F".parse().unwrap(),
+            "48000100".parse().unwrap(),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], r);
+
+        // Straddles the end of 000024 and the start of 000025.
+        let across: DevaddrRange = "49FFFFFF-4A000000".parse().unwrap();
+        let net_ids: Vec<_> = across.net_ids().map(|n| n.to_string()).collect();
+        assert_eq!(net_ids, ["000024", "000025"]);
+        let everything: DevaddrRange = "00000000/0".parse().unwrap();
+        assert_eq!(
+            everything.net_ids().count(),
+            64 + 64 + 512 + 2048 + 4096 + 8192 + 32768 + 131072
+        );
+        assert_eq!(everything.net_id_count(), 178_816);
+        assert_eq!(across.net_id_count(), 2);
+        let mixed: DevaddrRange = "7FFFFFF0-FC014C42".parse().unwrap();
+        assert_eq!(mixed.net_id_count(), mixed.net_ids().count() as u64);
+        assert_eq!(everything.split(0).unwrap().count(), 1);
+        assert_eq!(
+            everything.split(1).unwrap().last().unwrap().to_string(),
+            "80000000-FFFFFFFF"
+        );
+    }
+
+    #[test]
+    fn devaddr_at_index() {
+        let n = NetID::new("C00053").unwrap();
+        assert_eq!(n.devaddr_at(0).unwrap(), n.start_addr());
+        assert_eq!(n.devaddr_at(0x42).unwrap().to_string(), "FC014C42");
+        assert_eq!(n.devaddr_at(n.size() - 1).unwrap(), n.end_addr());
+        assert_eq!(
+            n.devaddr_at(n.size()),
+            Err(DevAddrError::AddrOutOfRange {
+                addr: 1024,
+                size: 1024
+            })
+        );
+    }
+
+    #[test]
+    fn decimal_devaddr() {
+        // Too long for hex, so it's read as decimal.
+        assert_eq!(Devaddr::new("1207959843").unwrap().to_string(), "48000123");
+    }
+}
@@ -0,0 +1,156 @@
+//! LoRa time on air, following Semtech's SX127x datasheet formula.
+
+use std::{fmt, ops::RangeInclusive};
+
+/// What LoRaWAN uses and the SX127x formula covers, SX126x SF5 and SF6 time
+/// their preamble and header differently.
+pub const SPREADING_FACTORS: RangeInclusive<u8> = 7..=12;
+/// Bandwidths LoRaWAN regions use, in Hz.
+pub const BANDWIDTHS: [u32; 3] = [125_000, 250_000, 500_000];
+
+/// Symbols at or above this long need low data rate optimization.
+const LDRO_SYMBOL_MS: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct LoraParams {
+    /// 7 to 12.
+    pub spreading_factor: u8,
+    pub bandwidth_hz: u32,
+    /// 1 to 4, for 4/5 to 4/8.
+    pub coding_rate: u8,
+    /// Programmed preamble symbols, the radio adds 4.25 more.
+    pub preamble_len: u16,
+    pub explicit_header: bool,
+    pub crc: bool,
+    pub low_data_rate_optimize: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatrError {
+    pub input: String,
+}
+
+impl Default for LoraParams {
+    /// A LoRaWAN uplink at SF7BW125.
+    fn default() -> Self {
+        Self {
+            spreading_factor: 7,
+            bandwidth_hz: 125_000,
+            coding_rate: 1,
+            preamble_len: 8,
+            explicit_header: true,
+            crc: true,
+            low_data_rate_optimize: false,
+        }
+    }
+}
+
+impl LoraParams {
+    pub fn symbol_time_ms(&self) -> f64 {
+        (1u32 << self.spreading_factor) as f64 / self.bandwidth_hz as f64 * 1000.0
+    }
+
+    /// Whether the symbols are long enough that the radio needs LDRO on.
+    pub fn ldro_required(&self) -> bool {
+        self.symbol_time_ms() >= LDRO_SYMBOL_MS
+    }
+
+    pub fn preamble_ms(&self) -> f64 {
+        (self.preamble_len as f64 + 4.25) * self.symbol_time_ms()
+    }
+
+    /// Symbols after the preamble for a `payload_len` byte payload.
+    pub fn payload_symbols(&self, payload_len: usize) -> u32 {
+        let sf = self.spreading_factor as i64;
+        let bits = 8 * payload_len as i64 - 4 * sf + 28 + 16 * self.crc as i64
+            - 20 * !self.explicit_header as i64;
+        let bits_per_block = 4 * (sf - 2 * self.low_data_rate_optimize as i64);
+        let blocks = (bits.max(0) + bits_per_block - 1) / bits_per_block;
+        8 + (blocks * (self.coding_rate as i64 + 4)) as u32
+    }
+
+    pub fn airtime_ms(&self, payload_len: usize) -> f64 {
+        self.preamble_ms() + self.payload_symbols(payload_len) as f64 * self.symbol_time_ms()
+    }
+}
+
+/// Spreading factor and bandwidth in Hz from a `datr` like `SF9BW125`, for
+/// the spreading factors and bandwidths LoRaWAN uses.
+pub fn parse_datr(datr: &str) -> Result<(u8, u32), DatrError> {
+    let error = || DatrError {
+        input: datr.to_string(),
+    };
+    let upper = datr.trim().trim_matches('"').to_uppercase();
+    let (sf, bw) = upper
+        .strip_prefix("SF")
+        .and_then(|rest| rest.split_once("BW"))
+        .ok_or_else(error)?;
+    let sf: u8 = sf.parse().map_err(|_| error())?;
+    let bw_khz: u32 = bw.parse().map_err(|_| error())?;
+    let bw = bw_khz.saturating_mul(1000);
+    if !SPREADING_FACTORS.contains(&sf) || !BANDWIDTHS.contains(&bw) {
+        return Err(error());
+    }
+    Ok((sf, bw))
+}
+
+/// How many `airtime_ms` long messages fit in an hour at `duty_cycle`.
+pub fn max_messages_per_hour(airtime_ms: f64, duty_cycle: f32) -> u32 {
+    (3_600_000.0 * duty_cycle as f64 / airtime_ms).floor() as u32
+}
+
+impl fmt::Display for DatrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} is not a LoRaWAN data rate like SF9BW125, SF7 to SF12 at 125, 250 or 500 kHz",
+            self.input.trim()
+        )
+    }
+}
+
+impl std::error::Error for DatrError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn airtimes() {
+        let sf7 = LoraParams::default();
+        assert!((sf7.symbol_time_ms() - 1.024).abs() < 1e-9);
+        assert_eq!(sf7.payload_symbols(13), 33);
+        assert!((sf7.airtime_ms(13) - 46.336).abs() < 1e-9);
+        assert!(!sf7.ldro_required());
+
+        let sf12 = LoraParams {
+            spreading_factor: 12,
+            low_data_rate_optimize: true,
+            ..sf7
+        };
+        assert!(sf12.ldro_required());
+        assert!((sf12.airtime_ms(13) - 1155.072).abs() < 1e-9);
+
+        // No payload and no CRC still sends the 8 symbol minimum.
+        let empty = LoraParams {
+            crc: false,
+            explicit_header: false,
+            ..sf12
+        };
+        assert_eq!(empty.payload_symbols(0), 8);
+
+        assert_eq!(max_messages_per_hour(1155.072, 0.01), 31);
+    }
+
+    #[test]
+    fn datr() {
+        assert_eq!(parse_datr("SF9BW125"), Ok((9, 125_000)));
+        assert_eq!(parse_datr("sf12bw500"), Ok((12, 500_000)));
+        assert!(parse_datr("SF7BW812").is_err());
+        assert!(parse_datr("SF7BW4294967").is_err());
+        assert!(parse_datr("SF6BW125").is_err());
+        assert!(parse_datr("SF13BW125").is_err());
+        assert!(parse_datr("50000").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    airtime::{self, LoraParams, BANDWIDTHS, SPREADING_FACTORS},
+    lorawan,
+    region::Region,
+};
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    params: LoraParams,
+    /// Follow the datasheet rule instead of the checkbox.
+    auto_ldro: bool,
+    payload_len: usize,
+    datr: String,
+    phy_payload: String,
+    region: String,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            params: Default::default(),
+            auto_ldro: true,
+            payload_len: 13,
+            datr: Default::default(),
+            phy_payload: Default::default(),
+            region: Region::EU868.name().to_string(),
+        }
+    }
+}
+
+impl State {
+    pub fn main_view(&mut self, ui: &mut egui::Ui) {
+        let Self {
+            params,
+            auto_ldro,
+            payload_len,
+            datr,
+            phy_payload,
+            region,
+        } = self;
+
+        ui.horizontal(|ui| {
+            ui.label("datr:");
+            ui.add(egui::TextEdit::singleline(datr).hint_text("SF9BW125"));
+            if ui.button("Apply").clicked() {
+                if let Ok((sf, bw)) = airtime::parse_datr(datr) {
+                    params.spreading_factor = sf;
+                    params.bandwidth_hz = bw;
+                }
+            }
+        });
+        if !datr.trim().is_empty() {
+            if let Err(e) = airtime::parse_datr(datr) {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("PHYPayload:");
+            ui.add(egui::TextEdit::singleline(phy_payload).hint_text("hex or base64"));
+            if ui.button("Use length").clicked() {
+                if let Ok((bytes, _)) = lorawan::decode_input(phy_payload) {
+                    *payload_len = bytes.len();
+                }
+            }
+        });
+        if !phy_payload.trim().is_empty() {
+            if let Err(e) = lorawan::decode_input(phy_payload) {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+            }
+        }
+
+        ui.separator();
+        egui::Grid::new("airtime_params").show(ui, |ui| {
+            ui.label("Spreading factor:");
+            ui.add(egui::Slider::new(&mut params.spreading_factor, SPREADING_FACTORS).prefix("SF"));
+            ui.end_row();
+
+            ui.label("Bandwidth:");
+            egui::ComboBox::from_id_source("airtime_bw")
+                .selected_text(format!("{} kHz", params.bandwidth_hz as f64 / 1000.0))
+                .show_ui(ui, |ui| {
+                    for bw in BANDWIDTHS {
+                        ui.selectable_value(
+                            &mut params.bandwidth_hz,
+                            bw,
+                            format!("{} kHz", bw / 1000),
+                        );
+                    }
+                });
+            ui.end_row();
+
+            ui.label("Coding rate:");
+            ui.horizontal(|ui| {
+                for cr in 1..=4 {
+                    ui.selectable_value(&mut params.coding_rate, cr, format!("4/{}", cr + 4));
+                }
+            });
+            ui.end_row();
+
+            ui.label("Preamble:");
+            ui.add(egui::DragValue::new(&mut params.preamble_len).suffix(" symbols"));
+            ui.end_row();
+
+            ui.label("Payload:");
+            ui.add(
+                egui::DragValue::new(payload_len)
+                    .clamp_range(0..=255)
+                    .suffix(" bytes"),
+            );
+            ui.end_row();
+
+            ui.label("");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut params.explicit_header, "Explicit header");
+                ui.checkbox(&mut params.crc, "CRC");
+            });
+            ui.end_row();
+
+            if *auto_ldro {
+                params.low_data_rate_optimize = params.ldro_required();
+            }
+            ui.label("");
+            ui.horizontal(|ui| {
+                ui.add_enabled(
+                    !*auto_ldro,
+                    egui::Checkbox::new(
+                        &mut params.low_data_rate_optimize,
+                        "Low data rate optimization",
+                    ),
+                );
+                ui.checkbox(auto_ldro, "Auto")
+                    .on_hover_text("On when symbols are 16 ms or longer");
+            });
+            ui.end_row();
+        });
+        if params.ldro_required() && !params.low_data_rate_optimize {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Symbols this long need low data rate optimization",
+            );
+        }
+
+        let airtime_ms = params.airtime_ms(*payload_len);
+        ui.separator();
+        egui::Grid::new("airtime_results")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Symbol time:");
+                ui.label(format!("{:.3} ms", params.symbol_time_ms()));
+                ui.end_row();
+
+                ui.label("Preamble:");
+                ui.label(format!("{:.3} ms", params.preamble_ms()));
+                ui.end_row();
+
+                ui.label("Payload symbols:");
+                ui.label(params.payload_symbols(*payload_len).to_string());
+                ui.end_row();
+
+                ui.strong("Airtime:");
+                ui.strong(format!("{airtime_ms:.3} ms"));
+                ui.end_row();
+            });
+
+        ui.separator();
+        let selected = region.parse::<Region>().ok();
+        egui::ComboBox::from_label("Region")
+            .selected_text(selected.map(|r| r.name()).unwrap_or("Choose"))
+            .show_ui(ui, |ui| {
+                for r in Region::ALL {
+                    if ui.selectable_label(selected == Some(r), r.name()).clicked() {
+                        *region = r.name().to_string();
+                    }
+                }
+            });
+        let Some(selected) = selected else {
+            return;
+        };
+        let rules = selected.params();
+        if let Some(duty_cycle) = rules.duty_cycle {
+            let per_hour = airtime::max_messages_per_hour(airtime_ms, duty_cycle);
+            ui.label(format!(
+                "{}% duty cycle: at most {per_hour} messages per hour, one every {:.1} s",
+                duty_cycle * 100.0,
+                airtime_ms / duty_cycle as f64 / 1000.0
+            ));
+        }
+        if let Some(dwell_time_ms) = rules.dwell_time_ms {
+            if airtime_ms > dwell_time_ms {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Over the {dwell_time_ms} ms dwell time, use a faster data rate or a shorter payload"),
+                );
+            } else {
+                ui.label(format!("Within the {dwell_time_ms} ms dwell time"));
+            }
+        }
+        if rules.duty_cycle.is_none() && rules.dwell_time_ms.is_none() {
+            ui.label(format!("{selected} has no duty cycle or dwell time limit"));
+        }
+    }
+}
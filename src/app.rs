@@ -4,11 +4,31 @@ use crate::*;
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct App {
+    view: View,
     net_id: net_id::State,
     roaming_token: roaming_token::State,
+    phy_payload: phy_payload::State,
+    join: join::State,
+    eui: eui_inspector::State,
+    packet_forwarder: packet_forwarder::State,
+    backend_interfaces: backend_interfaces::State,
+    airtime: airtime_calculator::State,
     styles: MyStyles,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+enum View {
+    #[default]
+    NetId,
+    RoamingToken,
+    PhyPayload,
+    Join,
+    Eui,
+    PacketForwarder,
+    BackendInterfaces,
+    Airtime,
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct MyStyles {
     pub button_spc_x: f32,
@@ -19,8 +39,15 @@ pub struct MyStyles {
 impl Default for App {
     fn default() -> Self {
         Self {
+            view: Default::default(),
             net_id: Default::default(),
             roaming_token: Default::default(),
+            phy_payload: Default::default(),
+            join: Default::default(),
+            eui: Default::default(),
+            packet_forwarder: Default::default(),
+            backend_interfaces: Default::default(),
+            airtime: Default::default(),
             styles: MyStyles {
                 button_spc_x: 15.0,
                 button_spc_y: 10.0,
@@ -60,29 +87,84 @@ impl eframe::App for App {
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let Self {
+            view,
             net_id,
             roaming_token,
+            phy_payload,
+            join,
+            eui,
+            packet_forwarder,
+            backend_interfaces,
+            airtime,
             styles,
         } = self;
 
+        egui::TopBottomPanel::top("views").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.selectable_value(view, View::NetId, "NetID / Devaddr");
+                ui.selectable_value(view, View::RoamingToken, "Roaming Token");
+                ui.selectable_value(view, View::PhyPayload, "PHYPayload");
+                ui.selectable_value(view, View::Join, "OTAA Join");
+                ui.selectable_value(view, View::Eui, "EUI-64");
+                ui.selectable_value(view, View::PacketForwarder, "Packet Forwarder");
+                ui.selectable_value(view, View::BackendInterfaces, "Backend Interfaces");
+                ui.selectable_value(view, View::Airtime, "Airtime");
+            });
+        });
+
         // Examples of how to create different panels and windows.
         // Pick whichever suits you.
         // Tip: a good default choice is to just keep the `CentralPanel`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
-            net_id.side_panel(ui);
-            ui.separator();
-            roaming_token.side_panel(ui);
-            ui.separator();
-            egui::widgets::global_dark_light_mode_buttons(ui);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                match view {
+                    View::NetId => net_id.side_panel(ui),
+                    View::RoamingToken => roaming_token.side_panel(ui),
+                    _ => (),
+                }
+                ui.separator();
+                egui::widgets::global_dark_light_mode_buttons(ui);
+            });
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
             // The central panel the region left after adding TopPanel's and SidePanel's
-            net_id.main_view(ui, styles);
-            ui.separator();
-            roaming_token.main_view(ui);
-            egui::warn_if_debug_build(ui);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let inspect = match view {
+                    View::NetId => {
+                        net_id.main_view(ui, styles);
+                        None
+                    }
+                    View::RoamingToken => {
+                        roaming_token.main_view(ui);
+                        None
+                    }
+                    View::PhyPayload => phy_payload.main_view(ui),
+                    View::Join => {
+                        if let Some(session_keys) = join.main_view(ui) {
+                            phy_payload.use_session_keys(&session_keys);
+                            *view = View::PhyPayload;
+                        }
+                        None
+                    }
+                    View::Eui => {
+                        eui.main_view(ui);
+                        None
+                    }
+                    View::PacketForwarder => packet_forwarder.main_view(ui),
+                    View::BackendInterfaces => backend_interfaces.main_view(ui),
+                    View::Airtime => {
+                        airtime.main_view(ui);
+                        None
+                    }
+                };
+                if let Some(devaddr) = inspect {
+                    net_id.inspect_devaddr(devaddr);
+                    *view = View::NetId;
+                }
+                egui::warn_if_debug_build(ui);
+            });
         });
     }
 }
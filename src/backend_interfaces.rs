@@ -0,0 +1,388 @@
+use crate::{
+    addressing::{Devaddr, NetID},
+    eui::{self, Eui64},
+    lorawan::PhyPayload,
+    operators,
+    packet_forwarder::show_json_fields,
+    phy_payload::show_phy_payload,
+    roaming_token::parse_token,
+};
+use serde_json::{Map, Value};
+
+/// Every Backend Interfaces message starts with these.
+const HEADER_FIELDS: &[&str] = &[
+    "ProtocolVersion",
+    "SenderID",
+    "ReceiverID",
+    "TransactionID",
+    "MessageType",
+];
+
+/// Added in 1.1 to tell apart NSs sharing a NetID.
+const NSID_FIELDS: &[&str] = &["SenderNSID", "ReceiverNSID"];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum BiVersion {
+    #[default]
+    V1_0,
+    V1_1,
+}
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+    version: BiVersion,
+}
+
+impl State {
+    /// Returns a Devaddr to look at in the NetID view when one is clicked.
+    pub fn main_view(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let Self { input, version } = self;
+
+        ui.horizontal(|ui| {
+            ui.label("Check against:");
+            ui.selectable_value(version, BiVersion::V1_0, "BI 1.0");
+            ui.selectable_value(version, BiVersion::V1_1, "BI 1.1");
+        });
+        ui.add(
+            egui::TextEdit::multiline(input)
+                .hint_text("PRStartReq, XmitDataReq, ProfileReq, HRStartReq or their answers")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY)
+                .desired_rows(6),
+        );
+
+        if input.trim().is_empty() {
+            return None;
+        }
+        let message = match serde_json::from_str::<Value>(input) {
+            Ok(Value::Object(message)) => message,
+            Ok(_) => {
+                ui.colored_label(ui.visuals().error_fg_color, "not a JSON object");
+                return None;
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, format!("JSON: {e}"));
+                return None;
+            }
+        };
+
+        let problems = validate(&message, *version);
+        if problems.is_empty() {
+            ui.label(format!(
+                "✔ All required {} fields are present",
+                message_type(&message).unwrap_or("header")
+            ));
+        }
+        for problem in problems {
+            ui.colored_label(ui.visuals().error_fg_color, problem);
+        }
+
+        let mut inspect = None;
+        egui::Grid::new("bi_message").striped(true).show(ui, |ui| {
+            for field in ["MessageType", "TransactionID"] {
+                if let Some(value) = message.get(field) {
+                    ui.label(format!("{field}:"));
+                    ui.monospace(text(value));
+                    ui.end_row();
+                }
+            }
+            for field in ["SenderID", "ReceiverID"] {
+                if let Some(id) = message.get(field).and_then(Value::as_str) {
+                    ui.label(format!("{field}:"));
+                    ui.monospace(id);
+                    match describe_id(id) {
+                        Ok(description) => ui.label(description),
+                        Err(e) => ui.colored_label(ui.visuals().error_fg_color, e),
+                    };
+                    ui.end_row();
+                }
+            }
+            if let Some(Value::Object(result)) = message.get("Result") {
+                let code = result.get("ResultCode").map(text).unwrap_or_default();
+                ui.label("Result:");
+                if code == "Success" {
+                    ui.label(code);
+                } else {
+                    ui.colored_label(ui.visuals().warn_fg_color, code);
+                }
+                if let Some(description) = result.get("Description") {
+                    ui.label(text(description));
+                }
+                ui.end_row();
+            }
+            if let Some(dev_eui) = message.get("DevEUI").and_then(Value::as_str) {
+                ui.label("DevEUI:");
+                ui.monospace(dev_eui);
+                if let Some(vendor) = Eui64::new(dev_eui).ok().and_then(|e| eui::vendor(&e)) {
+                    ui.label(&vendor.name);
+                }
+                ui.end_row();
+            }
+            if let Some(dev_addr) = dev_addr(&message) {
+                ui.label("DevAddr:");
+                match Devaddr::new(&dev_addr) {
+                    Ok(d) => {
+                        if ui
+                            .button(d.as_hex())
+                            .on_hover_text("Inspect in the NetID view")
+                            .clicked()
+                        {
+                            inspect = Some(d.as_hex());
+                        }
+                        ui.label(format!("NetID {}", d.net_id()));
+                    }
+                    Err(e) => {
+                        ui.monospace(&dev_addr);
+                        ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(phy) = message.get("PHYPayload").and_then(Value::as_str) {
+            ui.collapsing("PHYPayload", |ui| match PhyPayload::from_input(phy) {
+                Ok((phy, _)) => {
+                    if let Some(devaddr) = show_phy_payload(ui, &phy) {
+                        inspect = Some(devaddr);
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+            });
+        }
+        for field in ["ULMetaData", "DLMetaData"] {
+            if let Some(meta_data) = message.get(field) {
+                ui.collapsing(field, |ui| show_json_fields(ui, field, meta_data));
+            }
+        }
+
+        for (idx, token) in find_tokens(&Value::Object(message)).into_iter().enumerate() {
+            ui.separator();
+            ui.strong("FNSULToken");
+            ui.monospace(&token);
+            match parse_token(&token) {
+                Ok(token) => {
+                    egui::Grid::new(("fns_ul_token", idx))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Region:");
+                            ui.label(&token.region);
+                            ui.end_row();
+                            ui.label("Packet Time:");
+                            ui.label(token.packet_time.to_string());
+                            ui.end_row();
+                            ui.label("Route ID:");
+                            ui.label(&token.route_id);
+                            ui.end_row();
+                            ui.label("Gateway:");
+                            ui.label(&token.animal_name);
+                            ui.end_row();
+                        });
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+            }
+        }
+        inspect
+    }
+}
+
+fn message_type(message: &Map<String, Value>) -> Option<&str> {
+    message.get("MessageType").and_then(Value::as_str)
+}
+
+/// Fields `message_type` can't do without, and groups of which one is
+/// needed. `None` for messages this view doesn't know.
+fn required_fields(
+    message_type: &str,
+) -> Option<(&'static [&'static str], &'static [[&'static str; 2]])> {
+    Some(match message_type {
+        "PRStartReq" => (&["PHYPayload", "ULMetaData"], &[]),
+        "HRStartReq" => (&["PHYPayload", "ULMetaData", "MACVersion"], &[]),
+        "XmitDataReq" => (
+            &[],
+            &[["PHYPayload", "FRMPayload"], ["ULMetaData", "DLMetaData"]],
+        ),
+        "ProfileReq" => (&["DevEUI"], &[]),
+        "PRStartAns" | "HRStartAns" | "XmitDataAns" | "ProfileAns" => (&["Result"], &[]),
+        _ => return None,
+    })
+}
+
+/// Everything wrong with `message` for `version`.
+fn validate(message: &Map<String, Value>, version: BiVersion) -> Vec<String> {
+    let mut problems = vec![];
+    let mut require = |fields: &[&str]| {
+        for field in fields {
+            if !message.contains_key(*field) {
+                problems.push(format!("missing {field}"));
+            }
+        }
+    };
+    require(HEADER_FIELDS);
+    if version == BiVersion::V1_1 {
+        require(NSID_FIELDS);
+    }
+
+    let expected = match version {
+        BiVersion::V1_0 => "1.0",
+        BiVersion::V1_1 => "1.1",
+    };
+    if let Some(actual) = message.get("ProtocolVersion").and_then(Value::as_str) {
+        if actual != expected {
+            problems.push(format!(
+                "ProtocolVersion is {actual}, checking against {expected}"
+            ));
+        }
+    }
+    if version == BiVersion::V1_0 {
+        for field in NSID_FIELDS {
+            if message.contains_key(*field) {
+                problems.push(format!("{field} is new in 1.1"));
+            }
+        }
+    }
+
+    let Some(message_type) = message_type(message) else {
+        return problems;
+    };
+    match required_fields(message_type) {
+        Some((fields, one_of)) => {
+            for field in fields {
+                if !message.contains_key(*field) {
+                    problems.push(format!("{message_type} needs {field}"));
+                }
+            }
+            for [a, b] in one_of {
+                if !message.contains_key(*a) && !message.contains_key(*b) {
+                    problems.push(format!("{message_type} needs {a} or {b}"));
+                }
+            }
+        }
+        None => problems.push(format!("{message_type} is not a roaming message")),
+    }
+    if let Some(Value::Object(result)) = message.get("Result") {
+        if !result.contains_key("ResultCode") {
+            problems.push("Result needs a ResultCode".to_string());
+        }
+    }
+    problems
+}
+
+/// SenderID and ReceiverID are NetIDs for network servers and JoinEUIs for
+/// join servers.
+fn describe_id(id: &str) -> Result<String, String> {
+    let digits = id.trim().trim_start_matches("0x").len();
+    if digits > 6 {
+        let eui = Eui64::new(id).map_err(|e| format!("not a NetID or JoinEUI: {e}"))?;
+        let vendor = eui::vendor(&eui)
+            .map(|a| format!(", {}", a.name))
+            .unwrap_or_default();
+        return Ok(format!("JoinEUI{vendor}"));
+    }
+    let net_id = NetID::new(id.trim().trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    let operator = operators::lookup(&net_id)
+        .map(|op| format!(", {}", op.name))
+        .unwrap_or_default();
+    Ok(format!("NetID {net_id}{operator}"))
+}
+
+/// The DevAddr at the top level, or in the metadata for uplinks.
+fn dev_addr(message: &Map<String, Value>) -> Option<String> {
+    message
+        .get("DevAddr")
+        .or_else(|| message.get("ULMetaData")?.get("DevAddr"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Every FNSULToken anywhere in the message.
+fn find_tokens(value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .flat_map(|(key, value)| match (key.as_str(), value) {
+                ("FNSULToken", Value::String(token)) => vec![token.clone()],
+                _ => find_tokens(value),
+            })
+            .collect(),
+        Value::Array(values) => values.iter().flat_map(find_tokens).collect(),
+        _ => vec![],
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roaming_token::encode_token;
+
+    fn message(json: &str) -> Map<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn validate_messages() {
+        let pr_start = message(
+            r#"{"ProtocolVersion":"1.1","SenderID":"000024","ReceiverID":"000013",
+                "TransactionID":1,"MessageType":"PRStartReq","SenderNSID":"a",
+                "ReceiverNSID":"b","PHYPayload":"40","ULMetaData":{}}"#,
+        );
+        assert!(validate(&pr_start, BiVersion::V1_1).is_empty());
+        assert_eq!(
+            validate(&pr_start, BiVersion::V1_0),
+            [
+                "ProtocolVersion is 1.1, checking against 1.0",
+                "SenderNSID is new in 1.1",
+                "ReceiverNSID is new in 1.1",
+            ]
+        );
+
+        let xmit = message(
+            r#"{"ProtocolVersion":"1.0","SenderID":"000024","ReceiverID":"000013",
+                "TransactionID":1,"MessageType":"XmitDataReq","FRMPayload":"00"}"#,
+        );
+        assert_eq!(
+            validate(&xmit, BiVersion::V1_0),
+            ["XmitDataReq needs ULMetaData or DLMetaData"]
+        );
+
+        let ans = message(r#"{"MessageType":"PRStartAns","Result":{}}"#);
+        assert!(validate(&ans, BiVersion::V1_0).contains(&"missing SenderID".to_string()));
+        assert!(validate(&ans, BiVersion::V1_0).contains(&"Result needs a ResultCode".to_string()));
+    }
+
+    #[test]
+    fn ids_and_tokens() {
+        assert_eq!(
+            describe_id("000013").unwrap(),
+            "NetID 000013, The Things Network"
+        );
+        assert_eq!(
+            describe_id("0004A30B001C0530").unwrap(),
+            "JoinEUI, Microchip Technology"
+        );
+        assert!(describe_id("zz").is_err());
+
+        let mut bin = vec![0x01];
+        bin.extend([0x42; 32]);
+        let token = encode_token("US915", 1_000, "route", &bin);
+        let json: Value = serde_json::from_str(&format!(
+            r#"{{"DevAddr":"48000001","ULMetaData":{{"FNSULToken":"{token}","DevAddr":"26011BDA"}}}}"#
+        ))
+        .unwrap();
+        assert_eq!(find_tokens(&json), [token]);
+        assert_eq!(dev_addr(json.as_object().unwrap()).unwrap(), "48000001");
+    }
+}
@@ -0,0 +1,331 @@
+use crate::addressing::{Devaddr, NetID};
+use egui_extras::Column;
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+    /// Which field of each line holds the Devaddr, from 0.
+    column: usize,
+    sort_by: SortBy,
+    descending: bool,
+    /// Why the last dropped file couldn't be read.
+    #[serde(skip)]
+    drop_error: Option<String>,
+    /// Why the last copy failed, until the next copy or clear.
+    #[serde(skip)]
+    copy_error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+enum SortBy {
+    #[default]
+    Input,
+    Devaddr,
+    NetId,
+    MemType,
+    NwkAddr,
+    Addr,
+    Common,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Row {
+    devaddr: String,
+    net_id: String,
+    #[serde(rename = "type")]
+    mem_type: u8,
+    nwk_addr: u32,
+    addr_index: u32,
+    common: Option<String>,
+    #[serde(skip)]
+    num: u32,
+    #[serde(skip)]
+    net_id_num: u32,
+}
+
+impl State {
+    /// Add any files dropped on the window to the input. Called whether or
+    /// not the batch section is open so drops aren't lost, returns whether
+    /// there were any.
+    pub fn read_dropped_files(&mut self, ctx: &egui::Context) -> bool {
+        let files = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in &files {
+            match read_dropped(file) {
+                Ok(text) => {
+                    if !self.input.is_empty() && !self.input.ends_with('\n') {
+                        self.input.push('\n');
+                    }
+                    self.input.push_str(&text);
+                    self.drop_error = None;
+                }
+                Err(e) => self.drop_error = Some(e),
+            }
+        }
+        !files.is_empty()
+    }
+
+    pub fn main_view<'a>(
+        &mut self,
+        ui: &mut egui::Ui,
+        common: impl IntoIterator<Item = (&'a str, NetID)>,
+    ) {
+        let Self {
+            input,
+            column,
+            sort_by,
+            descending,
+            drop_error,
+            copy_error,
+        } = self;
+
+        ui.label("Paste Devaddrs one per line, or drop a text/CSV file on the window.");
+        if let Some(e) = drop_error {
+            ui.colored_label(ui.visuals().error_fg_color, e.as_str());
+        }
+        ui.add(
+            egui::TextEdit::multiline(input)
+                .hint_text("48000123\n0x78000001,sensor-1")
+                .desired_rows(4),
+        );
+        ui.horizontal(|ui| {
+            ui.label("Devaddr column:");
+            let mut shown = *column + 1;
+            ui.add(egui::DragValue::new(&mut shown).clamp_range(1..=usize::MAX))
+                .on_hover_text(
+                    "For CSV files, which comma, semicolon or tab separated field to read",
+                );
+            *column = shown - 1;
+        });
+
+        let common: Vec<_> = common.into_iter().collect();
+        let (mut rows, skipped) = classify(input, *column, &common);
+        sort(&mut rows, *sort_by, *descending);
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} Devaddrs", rows.len()));
+            if ui.button("Copy CSV").clicked() {
+                match to_csv(&rows) {
+                    Ok(csv) => {
+                        ui.output_mut(|o| o.copied_text = csv);
+                        *copy_error = None;
+                    }
+                    Err(e) => *copy_error = Some(e.to_string()),
+                }
+            }
+            if ui.button("Clear").clicked() {
+                input.clear();
+                *copy_error = None;
+            }
+        });
+        if let Some(e) = copy_error {
+            ui.colored_label(ui.visuals().error_fg_color, e.as_str());
+        }
+
+        if !skipped.is_empty() {
+            ui.collapsing(format!("{} entries skipped", skipped.len()), |ui| {
+                for (entry, e) in skipped.iter() {
+                    ui.label(format!("{entry}: {e}"));
+                }
+            });
+        }
+
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(400.0)
+            .columns(Column::auto().resizable(true), 5)
+            .column(Column::remainder())
+            .header(20.0, |mut header| {
+                for (column, text) in [
+                    (SortBy::Devaddr, "Devaddr"),
+                    (SortBy::NetId, "NetID"),
+                    (SortBy::MemType, "Type"),
+                    (SortBy::NwkAddr, "NwkAddr"),
+                    (SortBy::Addr, "Addr Index"),
+                    (SortBy::Common, "Common"),
+                ] {
+                    header.col(|ui| {
+                        let text = match (*sort_by == column, *descending) {
+                            (true, false) => format!("{text} ⏶"),
+                            (true, true) => format!("{text} ⏷"),
+                            _ => text.to_string(),
+                        };
+                        if ui.button(text).clicked() {
+                            if *sort_by == column {
+                                *descending = !*descending;
+                            } else {
+                                *sort_by = column;
+                                *descending = false;
+                            }
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(18.0, rows.len(), |mut row| {
+                    let r = &rows[row.index()];
+                    row.col(|ui| {
+                        ui.monospace(&r.devaddr);
+                    });
+                    row.col(|ui| {
+                        ui.monospace(&r.net_id);
+                    });
+                    row.col(|ui| {
+                        ui.label(r.mem_type.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(r.nwk_addr.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(r.addr_index.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(r.common.as_deref().unwrap_or_default());
+                    });
+                });
+            });
+    }
+}
+
+fn read_dropped(file: &egui::DroppedFile) -> Result<String, String> {
+    if let Some(bytes) = &file.bytes {
+        return String::from_utf8(bytes.to_vec()).map_err(|e| format!("{}: {e}", file.name));
+    }
+    match &file.path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display())),
+        None => Err(format!("{}: nothing to read", file.name)),
+    }
+}
+
+/// Classify the Devaddr in `column` of each line, returning the lines that
+/// didn't parse separately. A first line that looks like a column name rather
+/// than a mistyped Devaddr is taken to be a CSV header and dropped.
+fn classify(
+    input: &str,
+    column: usize,
+    common: &[(&str, NetID)],
+) -> (Vec<Row>, Vec<(String, String)>) {
+    let mut rows = vec![];
+    let mut skipped = vec![];
+
+    let lines = input.lines().filter(|line| !line.trim().is_empty());
+    for (idx, line) in lines.enumerate() {
+        let Some(entry) = line.split([',', ';', '\t']).nth(column) else {
+            skipped.push((line.to_string(), format!("no column {}", column + 1)));
+            continue;
+        };
+        let entry = entry.trim().trim_matches(|ch| ch == '"' || ch == '\'');
+        let hex = entry
+            .strip_prefix("0x")
+            .or_else(|| entry.strip_prefix("0X"))
+            .unwrap_or(entry);
+        match Devaddr::new(hex) {
+            Ok(d) => {
+                let net_id = d.net_id();
+                rows.push(Row {
+                    devaddr: d.as_hex(),
+                    net_id: net_id.as_hex(),
+                    mem_type: d.mem_type(),
+                    nwk_addr: d.nwk_addr(),
+                    addr_index: d.addr(),
+                    common: common
+                        .iter()
+                        .find(|(_, n)| n.contains(&d))
+                        .map(|(name, n)| format!("{name} ({n})")),
+                    num: d.num(),
+                    net_id_num: net_id.num(),
+                });
+            }
+            Err(_) if idx == 0 && is_column_name(hex) => (),
+            Err(e) => skipped.push((entry.to_string(), e.to_string())),
+        }
+    }
+
+    (rows, skipped)
+}
+
+/// Letters that can't be hex and no digits, like `devaddr` or `DevAddr`.
+fn is_column_name(entry: &str) -> bool {
+    entry
+        .chars()
+        .any(|ch| ch.is_alphabetic() && !ch.is_ascii_hexdigit())
+        && !entry.chars().any(|ch| ch.is_ascii_digit())
+}
+
+fn sort(rows: &mut [Row], sort_by: SortBy, descending: bool) {
+    match sort_by {
+        SortBy::Input => (),
+        SortBy::Devaddr => rows.sort_by_key(|r| r.num),
+        SortBy::NetId => rows.sort_by_key(|r| (r.net_id_num, r.num)),
+        SortBy::MemType => rows.sort_by_key(|r| (r.mem_type, r.num)),
+        SortBy::NwkAddr => rows.sort_by_key(|r| (r.nwk_addr, r.num)),
+        SortBy::Addr => rows.sort_by_key(|r| (r.addr_index, r.num)),
+        SortBy::Common => rows.sort_by(|a, b| (&a.common, a.num).cmp(&(&b.common, b.num))),
+    }
+    if descending {
+        rows.reverse();
+    }
+}
+
+fn to_csv(rows: &[Row]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_mixed_list() {
+        let helium = NetID::new("00003C").unwrap();
+        let input = "devaddr,name\n0x78000001, sensor-1\n\"48000123\";sensor-2\nFF000000\n";
+        let (rows, skipped) = classify(input, 0, &[("Helium", helium)]);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].net_id, "00003C");
+        assert_eq!(rows[0].addr_index, 1);
+        assert_eq!(rows[0].common.as_deref(), Some("Helium (00003C)"));
+        assert_eq!(rows[1].net_id, "000024");
+        assert_eq!(rows[1].common, None);
+
+        let skipped: Vec<_> = skipped.iter().map(|(entry, _)| entry.as_str()).collect();
+        assert_eq!(skipped, ["FF000000"]);
+
+        let csv = to_csv(&rows).unwrap();
+        assert_eq!(
+            csv.lines().next(),
+            Some("devaddr,net_id,type,nwk_addr,addr_index,common")
+        );
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("78000001,00003C,0,60,1,Helium (00003C)")
+        );
+    }
+
+    #[test]
+    fn classify_chosen_column() {
+        let input = "name\tdevaddr\nsensor-1\t48000123\nsensor-2\n";
+        let (rows, skipped) = classify(input, 1, &[]);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].devaddr, "48000123");
+        assert_eq!(
+            skipped,
+            [("sensor-2".to_string(), "no column 2".to_string())]
+        );
+    }
+
+    #[test]
+    fn bad_first_line_is_reported() {
+        for first in ["FF000000", "4800012G", "0x"] {
+            let (rows, skipped) = classify(&format!("{first}\n48000123\n"), 0, &[]);
+            assert_eq!(rows.len(), 1);
+            assert_eq!(skipped.len(), 1, "{first} was dropped");
+        }
+        let (_, skipped) = classify("Dev Addr\n48000123\n", 0, &[]);
+        assert!(skipped.is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    addressing::{Devaddr, NetID},
+    num_format::ToFormattedString,
+    operators,
+};
+
+const USAGE: &str = "\
+Usage:
+  bit_looker                         Launch the GUI
+  bit_looker netid <NETID> [--json]  Decode a hex NetID
+  bit_looker devaddr <DEVADDR> [--json]
+                                     Decode a hex (or decimal) Devaddr
+  bit_looker help                    Print this message";
+
+#[derive(serde::Serialize)]
+struct NetIdReport {
+    #[serde(rename = "type")]
+    mem_type: u8,
+    nwk_id: u32,
+    devaddr_nwk_id: u32,
+    operator: Option<String>,
+    hex: String,
+    dec: u32,
+    arr: [u8; 3],
+    size: u32,
+    addr_bits: usize,
+    subnet: String,
+    range: [String; 2],
+}
+
+#[derive(serde::Serialize)]
+struct DevaddrReport {
+    hex: String,
+    dec: u32,
+    arr: [u8; 4],
+    #[serde(rename = "type")]
+    mem_type: u8,
+    nwk_addr: u32,
+    net_id: String,
+    operator: Option<String>,
+    addr: u32,
+    size: u32,
+}
+
+impl NetIdReport {
+    fn new(n: &NetID) -> Self {
+        let start = n.start_addr();
+        let end = n.end_addr();
+        let [_, a, b, c] = n.num().to_be_bytes();
+        Self {
+            mem_type: n.mem_type(),
+            nwk_id: n.id(),
+            devaddr_nwk_id: n.nwk_id(),
+            operator: operators::lookup(n).map(|op| op.name.clone()),
+            hex: n.as_hex(),
+            dec: n.num(),
+            arr: [a, b, c],
+            size: n.size(),
+            addr_bits: n.addr_bits(),
+            subnet: format!("{}/{}", start.as_hex(), 32 - n.addr_bits()),
+            range: [start.as_hex(), end.as_hex()],
+        }
+    }
+
+    fn to_text(&self) -> String {
+        [
+            format!("Type:       {}", self.mem_type),
+            format!("NwkID:      {}", self.nwk_id),
+            format!("In Devaddr: {}", self.devaddr_nwk_id),
+            format!("Operator:   {}", operator_text(&self.operator)),
+            format!("Hex:        {}", self.hex),
+            format!("Dec:        {}", self.dec),
+            format!("Arr:        {:?}", self.arr),
+            format!(
+                "Size:       {} ({} bits)",
+                self.size.to_formatted_string(),
+                self.addr_bits
+            ),
+            format!("Subnet:     {}", self.subnet),
+            format!("Range:      {} -> {}", self.range[0], self.range[1]),
+        ]
+        .join("\n")
+    }
+}
+
+impl DevaddrReport {
+    fn new(d: &Devaddr) -> Self {
+        Self {
+            hex: d.as_hex(),
+            dec: d.num(),
+            arr: d.num().to_be_bytes(),
+            mem_type: d.mem_type(),
+            nwk_addr: d.nwk_addr(),
+            net_id: d.net_id().as_hex(),
+            operator: operators::lookup_devaddr(d).map(|op| op.name.clone()),
+            addr: d.addr(),
+            size: d.net_id().size(),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let per = ((self.addr + 1) as f32 / self.size as f32) * 100.0;
+        [
+            format!("Hex:        {}", self.hex),
+            format!("Dec:        {}", self.dec),
+            format!("Arr:        {:?}", self.arr),
+            format!("Type:       {}", self.mem_type),
+            format!("NwkAddr:    {}", self.nwk_addr),
+            format!("NetID:      {}", self.net_id),
+            format!("Operator:   {}", operator_text(&self.operator)),
+            format!(
+                "Addr:       {} of {} ({}%)",
+                (self.addr + 1).to_formatted_string(),
+                self.size.to_formatted_string(),
+                per
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/// Run the command line interface with `args` (excluding the program name).
+///
+/// Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    match execute(args) {
+        Ok(output) => {
+            println!("{output}");
+            0
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            2
+        }
+    }
+}
+
+fn execute(args: &[String]) -> Result<String, String> {
+    let json = args.iter().any(|a| a == "--json");
+    let positional: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|a| *a != "--json")
+        .collect();
+
+    match positional.as_slice() {
+        ["netid", input] => {
+            let n = NetID::new(input).map_err(|e| format!("invalid NetID {input:?}: {e}"))?;
+            render(&NetIdReport::new(&n), json, NetIdReport::to_text)
+        }
+        ["devaddr", input] => {
+            let d = Devaddr::new(input).map_err(|e| format!("invalid Devaddr {input:?}: {e}"))?;
+            render(&DevaddrReport::new(&d), json, DevaddrReport::to_text)
+        }
+        ["help"] | ["--help"] | ["-h"] => Ok(USAGE.to_string()),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn operator_text(operator: &Option<String>) -> &str {
+    operator.as_deref().unwrap_or("not in the bundled table")
+}
+
+fn render<T: serde::Serialize>(
+    report: &T,
+    json: bool,
+    to_text: impl Fn(&T) -> String,
+) -> Result<String, String> {
+    if json {
+        serde_json::to_string_pretty(report).map_err(|e| e.to_string())
+    } else {
+        Ok(to_text(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn netid_text() {
+        let out = execute(&args(&["netid", "00003C"])).unwrap();
+        assert!(out.contains("NwkID:      60"));
+        assert!(out.contains("Operator:   Helium"));
+        assert!(out.contains("Subnet:     78000000/7"));
+        assert!(out.contains("Range:      78000000 -> 79FFFFFF"));
+    }
+
+    #[test]
+    fn devaddr_json() {
+        let out = execute(&args(&["devaddr", "48000123", "--json"])).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["net_id"], "000024");
+        assert_eq!(value["type"], 0);
+        assert_eq!(value["addr"], 0x123);
+    }
+
+    #[test]
+    fn bad_args() {
+        assert!(execute(&args(&["netid"])).is_err());
+        assert!(execute(&args(&["netid", "zz"])).is_err());
+    }
+}
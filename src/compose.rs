@@ -0,0 +1,126 @@
+use crate::{
+    addressing::{Devaddr, NetID},
+    num_format::ToFormattedString,
+};
+use rand::Rng;
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    net_id: String,
+    index: String,
+}
+
+impl State {
+    /// `net_id_input` is what's in the main Net ID field, `common` fills the
+    /// picker and the composed Devaddr can be sent to `devaddr`.
+    pub fn main_view<'a>(
+        &mut self,
+        ui: &mut egui::Ui,
+        net_id_input: &str,
+        common: impl IntoIterator<Item = (&'a str, NetID)>,
+        devaddr: &mut String,
+    ) {
+        let Self { net_id, index } = self;
+
+        ui.horizontal(|ui| {
+            ui.label("Net ID:");
+            ui.add(egui::TextEdit::singleline(net_id).hint_text("Net ID"));
+            if ui
+                .add_enabled(
+                    NetID::new(net_id_input).is_ok(),
+                    egui::Button::new("Use Net ID above"),
+                )
+                .clicked()
+            {
+                *net_id = net_id_input.trim().to_string();
+            }
+            egui::ComboBox::from_id_source("compose_common")
+                .selected_text("Common")
+                .show_ui(ui, |ui| {
+                    for (name, n) in common {
+                        if ui.button(format!("{name} ({n})")).clicked() {
+                            *net_id = n.as_hex();
+                        }
+                    }
+                });
+        });
+
+        let n = match NetID::new(net_id) {
+            Ok(n) => n,
+            Err(e) => {
+                if !net_id.trim().is_empty() {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+                return;
+            }
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Address index:");
+            ui.add(egui::TextEdit::singleline(index).hint_text("66 or 0x42"));
+            if ui.button("Random").clicked() {
+                let addr = rand::thread_rng().gen_range(0..n.size());
+                *index = addr.to_string();
+            }
+        });
+        ui.label(format!(
+            "{} Devaddrs, index 0 to {}",
+            n.size().to_formatted_string(),
+            (n.size() - 1).to_formatted_string()
+        ));
+
+        if index.trim().is_empty() {
+            return;
+        }
+        match compose(&n, index) {
+            Ok(d) => {
+                ui.horizontal(|ui| {
+                    ui.label("Devaddr:");
+                    ui.monospace(d.as_hex());
+                    if ui.button("Inspect").clicked() {
+                        *devaddr = d.as_hex();
+                    }
+                    if ui.button("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = d.as_hex());
+                    }
+                });
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+    }
+}
+
+/// Decimal, or hex with a `0x` prefix.
+fn parse_index(input: &str) -> Result<u32, String> {
+    let input = input.trim().replace('_', "");
+    let parsed = match input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => input.parse(),
+    };
+    parsed.map_err(|e| format!("address index {input:?}: {e}"))
+}
+
+fn compose(net_id: &NetID, index: &str) -> Result<Devaddr, String> {
+    parse_index(index).and_then(|addr| net_id.devaddr_at(addr).map_err(|e| e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_from_index() {
+        let n = NetID::new("00003C").unwrap();
+        assert_eq!(compose(&n, "66").unwrap().to_string(), "78000042");
+        assert_eq!(compose(&n, "0x42").unwrap().to_string(), "78000042");
+        assert_eq!(compose(&n, "33_554_431").unwrap(), n.end_addr());
+        assert!(compose(&n, "33554432").is_err());
+        assert!(compose(&n, "42h").is_err());
+    }
+}
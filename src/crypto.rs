@@ -0,0 +1,708 @@
+//! LoRaWAN frame security: MICs, payload encryption and OTAA joins.
+//!
+//! Pure Rust AES so it runs the same natively and in the browser.
+use crate::lorawan::{
+    DataPayload, JoinAccept, MType, MacPayload, PhyPayload, PhyPayloadError, MIC_LEN,
+};
+use aes::{
+    cipher::{BlockEncrypt, KeyInit},
+    Aes128,
+};
+use cmac::{Cmac, Mac};
+use std::fmt;
+
+pub type Key = [u8; 16];
+
+/// Highest FCnt MSB tried when looking for why a MIC failed.
+const MAX_MSB_GUESS: u16 = 0xFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKeys {
+    V1_0 {
+        nwk_s_key: Key,
+        app_s_key: Option<Key>,
+    },
+    V1_1 {
+        f_nwk_s_int_key: Key,
+        s_nwk_s_int_key: Key,
+        nwk_s_enc_key: Key,
+        app_s_key: Option<Key>,
+    },
+}
+
+/// A device's root keys. 1.0 devices only have the one, which the spec
+/// calls AppKey but does the job of 1.1's NwkKey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootKeys {
+    V1_0 { app_key: Key },
+    V1_1 { nwk_key: Key, app_key: Key },
+}
+
+/// What the device sends in its Join-Request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct JoinParams {
+    pub join_eui: u64,
+    pub dev_eui: u64,
+    pub dev_nonce: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenedJoinAccept {
+    pub accept: JoinAccept,
+    /// The MIC the frame carried, once decrypted.
+    pub received_mic: [u8; MIC_LEN],
+    pub computed_mic: [u8; MIC_LEN],
+}
+
+/// What the MIC covers beyond the frame itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MicParams {
+    /// Upper 16 bits of the frame counter, which aren't sent.
+    pub fcnt_msb: u16,
+    /// 1.1 only: the FCnt of the confirmed frame being acknowledged.
+    pub conf_fcnt: u16,
+    /// 1.1 uplinks only: the data rate and channel index it was sent on.
+    pub tx_dr: u8,
+    pub tx_ch: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    /// Not 32 hex digits.
+    InvalidKey(String),
+    /// Only data frames carry session MICs and FRMPayloads.
+    NotDataFrame,
+    /// The AppSKey is needed to decrypt an FPort other than 0.
+    MissingAppSKey,
+    NotJoinAccept,
+    Frame(PhyPayloadError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MicFailure {
+    pub received: [u8; MIC_LEN],
+    pub computed: [u8; MIC_LEN],
+    /// Best guess at what's wrong.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dir {
+    Up = 0,
+    Down = 1,
+}
+
+pub fn parse_key(input: &str) -> Result<Key, CryptoError> {
+    let hex: String = input.split_whitespace().collect();
+    let bytes = hex::decode(&hex).map_err(|_| CryptoError::InvalidKey(input.to_string()))?;
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidKey(input.to_string()))
+}
+
+pub fn aes_cmac(key: &Key, data: &[u8]) -> [u8; 16] {
+    let mut mac = <Cmac<Aes128> as Mac>::new(key.into());
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+pub fn aes_encrypt(key: &Key, block: &[u8; 16]) -> [u8; 16] {
+    let cipher = Aes128::new(key.into());
+    let mut block = (*block).into();
+    cipher.encrypt_block(&mut block);
+    block.into()
+}
+
+impl SessionKeys {
+    fn app_s_key(&self) -> Option<Key> {
+        match self {
+            Self::V1_0 { app_s_key, .. } | Self::V1_1 { app_s_key, .. } => *app_s_key,
+        }
+    }
+
+    /// Encrypts FPort 0 payloads, and FOpts in 1.1.
+    fn nwk_enc_key(&self) -> Key {
+        match self {
+            Self::V1_0 { nwk_s_key, .. } => *nwk_s_key,
+            Self::V1_1 { nwk_s_enc_key, .. } => *nwk_s_enc_key,
+        }
+    }
+}
+
+fn data(phy: &PhyPayload) -> Result<&DataPayload, CryptoError> {
+    match &phy.payload {
+        MacPayload::Data(data) => Ok(data),
+        _ => Err(CryptoError::NotDataFrame),
+    }
+}
+
+fn full_fcnt(data: &DataPayload, fcnt_msb: u16) -> u32 {
+    (fcnt_msb as u32) << 16 | data.fhdr.fcnt as u32
+}
+
+/// The B0/B1 block the MIC is computed over, before the message.
+fn mic_block(
+    conf_fcnt: u16,
+    tx_dr: u8,
+    tx_ch: u8,
+    dir: Dir,
+    dev_addr: u32,
+    fcnt: u32,
+    len: usize,
+) -> [u8; 16] {
+    let mut b = [0u8; 16];
+    b[0] = 0x49;
+    b[1..3].copy_from_slice(&conf_fcnt.to_le_bytes());
+    b[3] = tx_dr;
+    b[4] = tx_ch;
+    b[5] = dir as u8;
+    b[6..10].copy_from_slice(&dev_addr.to_le_bytes());
+    b[10..14].copy_from_slice(&fcnt.to_le_bytes());
+    b[15] = len as u8;
+    b
+}
+
+fn cmac_with_block(key: &Key, block: &[u8; 16], msg: &[u8]) -> [u8; 16] {
+    let mut data = block.to_vec();
+    data.extend_from_slice(msg);
+    aes_cmac(key, &data)
+}
+
+/// The MIC `keys` give for a data frame.
+pub fn compute_mic(
+    phy: &PhyPayload,
+    keys: &SessionKeys,
+    params: &MicParams,
+) -> Result<[u8; MIC_LEN], CryptoError> {
+    let data = data(phy)?;
+    let msg = &phy.bytes[..phy.bytes.len() - MIC_LEN];
+    let dev_addr = data.fhdr.dev_addr;
+    let fcnt = full_fcnt(data, params.fcnt_msb);
+    let dir = dir_of(phy);
+
+    let mut mic = [0u8; MIC_LEN];
+    match (keys, dir) {
+        (SessionKeys::V1_0 { nwk_s_key, .. }, _) => {
+            let b0 = mic_block(0, 0, 0, dir, dev_addr, fcnt, msg.len());
+            mic.copy_from_slice(&cmac_with_block(nwk_s_key, &b0, msg)[..4]);
+        }
+        (
+            SessionKeys::V1_1 {
+                f_nwk_s_int_key,
+                s_nwk_s_int_key,
+                ..
+            },
+            Dir::Up,
+        ) => {
+            let b0 = mic_block(0, 0, 0, dir, dev_addr, fcnt, msg.len());
+            let b1 = mic_block(
+                params.conf_fcnt,
+                params.tx_dr,
+                params.tx_ch,
+                dir,
+                dev_addr,
+                fcnt,
+                msg.len(),
+            );
+            mic[..2].copy_from_slice(&cmac_with_block(s_nwk_s_int_key, &b1, msg)[..2]);
+            mic[2..].copy_from_slice(&cmac_with_block(f_nwk_s_int_key, &b0, msg)[..2]);
+        }
+        (
+            SessionKeys::V1_1 {
+                s_nwk_s_int_key, ..
+            },
+            Dir::Down,
+        ) => {
+            let b0 = mic_block(params.conf_fcnt, 0, 0, dir, dev_addr, fcnt, msg.len());
+            mic.copy_from_slice(&cmac_with_block(s_nwk_s_int_key, &b0, msg)[..4]);
+        }
+    }
+    Ok(mic)
+}
+
+/// Check the frame's MIC, and when it doesn't match try the usual mistakes
+/// to explain why.
+pub fn verify_mic(
+    phy: &PhyPayload,
+    keys: &SessionKeys,
+    params: &MicParams,
+) -> Result<Result<(), MicFailure>, CryptoError> {
+    let received = phy.mic.ok_or(CryptoError::NotDataFrame)?;
+    let computed = compute_mic(phy, keys, params)?;
+    if computed == received {
+        return Ok(Ok(()));
+    }
+    let reason = explain_mic_failure(phy, keys, params, &received)?;
+    Ok(Err(MicFailure {
+        received,
+        computed,
+        reason,
+    }))
+}
+
+fn explain_mic_failure(
+    phy: &PhyPayload,
+    keys: &SessionKeys,
+    params: &MicParams,
+    received: &[u8; MIC_LEN],
+) -> Result<String, CryptoError> {
+    let matches = |keys: &SessionKeys, params: &MicParams| {
+        compute_mic(phy, keys, params).map(|mic| mic == *received)
+    };
+
+    for fcnt_msb in (0..=MAX_MSB_GUESS).filter(|msb| *msb != params.fcnt_msb) {
+        let guess = MicParams {
+            fcnt_msb,
+            ..*params
+        };
+        if matches(keys, &guess)? {
+            let fcnt = full_fcnt(data(phy)?, fcnt_msb);
+            return Ok(format!(
+                "matches with FCnt MSB {fcnt_msb:#06X} (FCnt {fcnt}), the 16 bit FCnt on air has rolled over"
+            ));
+        }
+    }
+
+    match *keys {
+        SessionKeys::V1_0 {
+            nwk_s_key,
+            app_s_key,
+        } => {
+            if let Some(app_s_key) = app_s_key {
+                let swapped = SessionKeys::V1_0 {
+                    nwk_s_key: app_s_key,
+                    app_s_key: Some(nwk_s_key),
+                };
+                if matches(&swapped, params)? {
+                    return Ok("matches with the AppSKey, the keys are swapped".to_string());
+                }
+            }
+        }
+        SessionKeys::V1_1 {
+            f_nwk_s_int_key, ..
+        } => {
+            let as_1_0 = SessionKeys::V1_0 {
+                nwk_s_key: f_nwk_s_int_key,
+                app_s_key: None,
+            };
+            if matches(&as_1_0, params)? {
+                return Ok(
+                    "matches LoRaWAN 1.0 with the FNwkSIntKey, the device is not using 1.1"
+                        .to_string(),
+                );
+            }
+            if phy.mhdr.mtype.is_uplink() {
+                let computed = compute_mic(phy, keys, params)?;
+                if computed[2..] == received[2..] {
+                    return Ok("the FNwkSIntKey half matches, check the SNwkSIntKey, ConfFCnt, TxDr and TxCh".to_string());
+                }
+                if computed[..2] == received[..2] {
+                    return Ok("the SNwkSIntKey half matches, check the FNwkSIntKey".to_string());
+                }
+            }
+        }
+    }
+
+    Ok(format!(
+        "no FCnt MSB up to {MAX_MSB_GUESS:#X} matches, the network key is wrong or the frame was changed"
+    ))
+}
+
+/// XOR `payload` with the keystream LoRaWAN encrypts FRMPayloads with,
+/// which both encrypts and decrypts.
+fn crypt(key: &Key, dir: Dir, dev_addr: u32, fcnt: u32, payload: &[u8]) -> Vec<u8> {
+    payload
+        .chunks(16)
+        .enumerate()
+        .flat_map(|(idx, chunk)| {
+            let mut a = [0u8; 16];
+            a[0] = 0x01;
+            a[5] = dir as u8;
+            a[6..10].copy_from_slice(&dev_addr.to_le_bytes());
+            a[10..14].copy_from_slice(&fcnt.to_le_bytes());
+            a[15] = (idx as u8).wrapping_add(1);
+            let s = aes_encrypt(key, &a);
+            chunk.iter().zip(s).map(|(b, s)| b ^ s).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn dir_of(phy: &PhyPayload) -> Dir {
+    if phy.mhdr.mtype.is_uplink() {
+        Dir::Up
+    } else {
+        Dir::Down
+    }
+}
+
+pub fn decrypt_frm_payload(
+    phy: &PhyPayload,
+    keys: &SessionKeys,
+    fcnt_msb: u16,
+) -> Result<Vec<u8>, CryptoError> {
+    let data = data(phy)?;
+    let key = match data.fport {
+        Some(0) => keys.nwk_enc_key(),
+        _ => keys.app_s_key().ok_or(CryptoError::MissingAppSKey)?,
+    };
+    Ok(crypt(
+        &key,
+        dir_of(phy),
+        data.fhdr.dev_addr,
+        full_fcnt(data, fcnt_msb),
+        &data.frm_payload,
+    ))
+}
+
+/// FOpts are only encrypted from 1.1 on, 1.0 ones come back as they are.
+pub fn decrypt_fopts(
+    phy: &PhyPayload,
+    keys: &SessionKeys,
+    fcnt_msb: u16,
+) -> Result<Vec<u8>, CryptoError> {
+    let data = data(phy)?;
+    let SessionKeys::V1_1 { nwk_s_enc_key, .. } = keys else {
+        return Ok(data.fhdr.fopts.clone());
+    };
+    let dir = dir_of(phy);
+    // The 1.1 errata's A block: A[4] names the downlink counter, 1 for
+    // NFCntDown and 2 for AFCntDown when there's an application FPort, and
+    // A[15] is 1. FOpts are at most 15 bytes so one block covers them.
+    let mut a = [0u8; 16];
+    a[0] = 0x01;
+    a[4] = match (dir, data.fport) {
+        (Dir::Up, _) => 0x00,
+        (Dir::Down, Some(fport)) if fport > 0 => 0x02,
+        (Dir::Down, _) => 0x01,
+    };
+    a[5] = dir as u8;
+    a[6..10].copy_from_slice(&data.fhdr.dev_addr.to_le_bytes());
+    a[10..14].copy_from_slice(&full_fcnt(data, fcnt_msb).to_le_bytes());
+    a[15] = 0x01;
+    let s = aes_encrypt(nwk_s_enc_key, &a);
+    Ok(data.fhdr.fopts.iter().zip(s).map(|(b, s)| b ^ s).collect())
+}
+
+impl RootKeys {
+    /// Signs Join-Requests and encrypts Join-Accepts.
+    fn nwk_key(&self) -> Key {
+        match self {
+            Self::V1_0 { app_key } => *app_key,
+            Self::V1_1 { nwk_key, .. } => *nwk_key,
+        }
+    }
+}
+
+/// A signed Join-Request PHYPayload.
+pub fn build_join_request(params: &JoinParams, keys: &RootKeys) -> Vec<u8> {
+    let mut frame = vec![(MType::JoinRequest as u8) << 5];
+    frame.extend(params.join_eui.to_le_bytes());
+    frame.extend(params.dev_eui.to_le_bytes());
+    frame.extend(params.dev_nonce.to_le_bytes());
+    let mic = aes_cmac(&keys.nwk_key(), &frame);
+    frame.extend(&mic[..MIC_LEN]);
+    frame
+}
+
+/// Decrypt a Join-Accept sent in answer to a Join-Request and check its MIC.
+pub fn open_join_accept(
+    phy: &PhyPayload,
+    keys: &RootKeys,
+    params: &JoinParams,
+) -> Result<OpenedJoinAccept, CryptoError> {
+    let MacPayload::JoinAccept(encrypted) = &phy.payload else {
+        return Err(CryptoError::NotJoinAccept);
+    };
+    // The network encrypts with AES decrypt so devices only need encrypt.
+    let key = keys.nwk_key();
+    let decrypted: Vec<u8> = encrypted
+        .chunks_exact(16)
+        .flat_map(|block| aes_encrypt(&key, block.try_into().unwrap()))
+        .collect();
+    let (fields, received_mic) = decrypted.split_at(decrypted.len() - MIC_LEN);
+    let accept = JoinAccept::from_bytes(fields).map_err(CryptoError::Frame)?;
+
+    let mut msg = vec![phy.bytes[0]];
+    msg.extend_from_slice(fields);
+    let cmac = match keys {
+        RootKeys::V1_1 { nwk_key, .. } if accept.opt_neg() => {
+            let mut signed = vec![0xFF];
+            signed.extend(params.join_eui.to_le_bytes());
+            signed.extend(params.dev_nonce.to_le_bytes());
+            signed.extend(msg);
+            let mut block = [0u8; 16];
+            block[0] = 0x06;
+            block[1..9].copy_from_slice(&params.dev_eui.to_le_bytes());
+            aes_cmac(&aes_encrypt(nwk_key, &block), &signed)
+        }
+        _ => aes_cmac(&key, &msg),
+    };
+
+    Ok(OpenedJoinAccept {
+        accept,
+        received_mic: received_mic.try_into().unwrap(),
+        computed_mic: cmac[..MIC_LEN].try_into().unwrap(),
+    })
+}
+
+/// Session keys for the device after `accept`. A 1.1 device falls back to
+/// 1.0 derivation when the join server didn't set OptNeg.
+pub fn derive_session_keys(
+    accept: &JoinAccept,
+    keys: &RootKeys,
+    params: &JoinParams,
+) -> SessionKeys {
+    let derive = |key: &Key, prefix: u8, middle: &[u8]| {
+        let mut block = [0u8; 16];
+        block[0] = prefix;
+        block[1..4].copy_from_slice(&accept.join_nonce.to_le_bytes()[..3]);
+        block[4..4 + middle.len()].copy_from_slice(middle);
+        let end = 4 + middle.len();
+        block[end..end + 2].copy_from_slice(&params.dev_nonce.to_le_bytes());
+        aes_encrypt(key, &block)
+    };
+
+    match keys {
+        RootKeys::V1_1 { nwk_key, app_key } if accept.opt_neg() => {
+            let join_eui = params.join_eui.to_le_bytes();
+            SessionKeys::V1_1 {
+                f_nwk_s_int_key: derive(nwk_key, 0x01, &join_eui),
+                s_nwk_s_int_key: derive(nwk_key, 0x03, &join_eui),
+                nwk_s_enc_key: derive(nwk_key, 0x04, &join_eui),
+                app_s_key: Some(derive(app_key, 0x02, &join_eui)),
+            }
+        }
+        // A 1.1 device without OptNeg falls back to 1.0 keys and MICs.
+        _ => {
+            let key = keys.nwk_key();
+            let net_id = &accept.net_id.to_le_bytes()[..3];
+            SessionKeys::V1_0 {
+                nwk_s_key: derive(&key, 0x01, net_id),
+                app_s_key: Some(derive(&key, 0x02, net_id)),
+            }
+        }
+    }
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey(input) => write!(f, "{input:?} is not a 32 hex digit key"),
+            Self::NotDataFrame => write!(f, "only data frames use session keys"),
+            Self::MissingAppSKey => write!(f, "an AppSKey is needed for FPorts other than 0"),
+            Self::NotJoinAccept => write!(f, "not a Join-Accept"),
+            Self::Frame(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NWK_S_KEY: &str = "44024241ed4ce9a68c6a8bc055233fd3";
+    const APP_S_KEY: &str = "ec925802ae430ca77fd3dd73cb2cc588";
+
+    fn v1_0() -> SessionKeys {
+        SessionKeys::V1_0 {
+            nwk_s_key: parse_key(NWK_S_KEY).unwrap(),
+            app_s_key: Some(parse_key(APP_S_KEY).unwrap()),
+        }
+    }
+
+    #[test]
+    fn lorawan_1_0_frame() {
+        let (phy, _) = PhyPayload::from_input("40F17DBE4900020001954378762B11FF0D").unwrap();
+        let keys = v1_0();
+        assert_eq!(verify_mic(&phy, &keys, &MicParams::default()), Ok(Ok(())));
+        assert_eq!(decrypt_frm_payload(&phy, &keys, 0).unwrap(), b"test");
+    }
+
+    #[test]
+    fn explain_failures() {
+        let (phy, _) = PhyPayload::from_input("40F17DBE4900020001954378762B11FF0D").unwrap();
+        let wrong_msb = MicParams {
+            fcnt_msb: 3,
+            ..Default::default()
+        };
+        let failure = verify_mic(&phy, &v1_0(), &wrong_msb).unwrap().unwrap_err();
+        assert!(
+            failure.reason.contains("FCnt MSB 0x0000"),
+            "{}",
+            failure.reason
+        );
+
+        let swapped = SessionKeys::V1_0 {
+            nwk_s_key: parse_key(APP_S_KEY).unwrap(),
+            app_s_key: Some(parse_key(NWK_S_KEY).unwrap()),
+        };
+        let failure = verify_mic(&phy, &swapped, &MicParams::default())
+            .unwrap()
+            .unwrap_err();
+        assert!(failure.reason.contains("swapped"), "{}", failure.reason);
+
+        let as_1_1 = SessionKeys::V1_1 {
+            f_nwk_s_int_key: parse_key(NWK_S_KEY).unwrap(),
+            s_nwk_s_int_key: [0; 16],
+            nwk_s_enc_key: [0; 16],
+            app_s_key: None,
+        };
+        let failure = verify_mic(&phy, &as_1_1, &MicParams::default())
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            failure.reason.contains("not using 1.1"),
+            "{}",
+            failure.reason
+        );
+    }
+
+    const V1_1_KEYS: SessionKeys = SessionKeys::V1_1 {
+        f_nwk_s_int_key: [1; 16],
+        s_nwk_s_int_key: [2; 16],
+        nwk_s_enc_key: [3; 16],
+        app_s_key: Some([4; 16]),
+    };
+
+    // Frames from 26011BDA built outside this crate the way LoRaMac-node and
+    // ChirpStack do it, with the 1.1 errata's FOpts A block.
+    #[test]
+    fn lorawan_1_1_uplink() {
+        let params = MicParams {
+            tx_dr: 3,
+            tx_ch: 5,
+            ..Default::default()
+        };
+        // FCnt 7, FOpts 0203, FPort 1.
+        let (phy, _) = PhyPayload::from_input("40DA1B0126020700445D015DB504375ABA").unwrap();
+        assert_eq!(verify_mic(&phy, &V1_1_KEYS, &params), Ok(Ok(())));
+        assert_eq!(decrypt_fopts(&phy, &V1_1_KEYS, 0).unwrap(), [0x02, 0x03]);
+        assert_eq!(decrypt_frm_payload(&phy, &V1_1_KEYS, 0).unwrap(), b"hi");
+
+        let wrong_channel = MicParams { tx_ch: 6, ..params };
+        let failure = verify_mic(&phy, &V1_1_KEYS, &wrong_channel)
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            failure.reason.contains("FNwkSIntKey half matches"),
+            "{}",
+            failure.reason
+        );
+    }
+
+    #[test]
+    fn lorawan_1_1_downlink_fopts() {
+        // FCnt 5, FOpts 060708 and FPort 1, so AFCntDown.
+        let (phy, _) = PhyPayload::from_input("60DA1B01260305001831FF014129D10781CD").unwrap();
+        let params = MicParams::default();
+        assert_eq!(verify_mic(&phy, &V1_1_KEYS, &params), Ok(Ok(())));
+        assert_eq!(
+            decrypt_fopts(&phy, &V1_1_KEYS, 0).unwrap(),
+            [0x06, 0x07, 0x08]
+        );
+        assert_eq!(decrypt_frm_payload(&phy, &V1_1_KEYS, 0).unwrap(), b"ok");
+
+        // FCnt 6, FOpts 02 and no FPort, so NFCntDown.
+        let (phy, _) = PhyPayload::from_input("60DA1B0126010600826F322AA3").unwrap();
+        assert_eq!(verify_mic(&phy, &V1_1_KEYS, &params), Ok(Ok(())));
+        assert_eq!(decrypt_fopts(&phy, &V1_1_KEYS, 0).unwrap(), [0x02]);
+    }
+
+    const JOIN: JoinParams = JoinParams {
+        join_eui: 0x70B3D57ED0000001,
+        dev_eui: 0x0004A30B001C0530,
+        dev_nonce: 0x1234,
+    };
+
+    fn key(hex: &str) -> Key {
+        parse_key(hex).unwrap()
+    }
+
+    #[test]
+    fn lorawan_1_0_join() {
+        let keys = RootKeys::V1_0 {
+            app_key: key("B6B53F4A168A7A88BDF7EA135CE9CFCA"),
+        };
+        assert_eq!(
+            hex::encode_upper(build_join_request(&JOIN, &keys)),
+            "00010000D07ED5B37030051C000BA304003412C885875C"
+        );
+
+        let (phy, _) = PhyPayload::from_input("209848577242723BE2793FDB047BBC66EF").unwrap();
+        let opened = open_join_accept(&phy, &keys, &JOIN).unwrap();
+        assert_eq!(opened.received_mic, opened.computed_mic);
+        assert_eq!(opened.accept.join_nonce, 0x0A0B0C);
+        assert_eq!(opened.accept.net_id, 0x13);
+        assert_eq!(opened.accept.dev_addr, 0x26011BDA);
+        assert_eq!(opened.accept.rx_delay, 1);
+        assert_eq!(
+            derive_session_keys(&opened.accept, &keys, &JOIN),
+            SessionKeys::V1_0 {
+                nwk_s_key: key("61e6ba89df224e34ab5d598891bf6296"),
+                app_s_key: Some(key("a366c1c86ce78a7cdf90a6d6b851e6f6")),
+            }
+        );
+    }
+
+    #[test]
+    fn lorawan_1_1_join() {
+        let keys = RootKeys::V1_1 {
+            nwk_key: key("2B7E151628AED2A6ABF7158809CF4F3C"),
+            app_key: key("B6B53F4A168A7A88BDF7EA135CE9CFCA"),
+        };
+        assert_eq!(
+            hex::encode_upper(build_join_request(&JOIN, &keys)),
+            "00010000D07ED5B37030051C000BA3040034124F5CA349"
+        );
+
+        let (phy, _) = PhyPayload::from_input("2040DAA8DC82447CDEDF18AEA9BD8163C4").unwrap();
+        let opened = open_join_accept(&phy, &keys, &JOIN).unwrap();
+        assert!(opened.accept.opt_neg());
+        assert_eq!(opened.received_mic, opened.computed_mic);
+        assert_eq!(
+            derive_session_keys(&opened.accept, &keys, &JOIN),
+            SessionKeys::V1_1 {
+                f_nwk_s_int_key: key("4d4a426f400eb73748ae75caaf6effa9"),
+                s_nwk_s_int_key: key("ed631bcdcb829c32e6ea6013ee90b363"),
+                nwk_s_enc_key: key("666ee8eb1b7a926412ad44d70ebb56ca"),
+                app_s_key: Some(key("f829802eeace3b0ea20c44bc78c92825")),
+            }
+        );
+
+        let wrong_nonce = JoinParams {
+            dev_nonce: 0x1235,
+            ..JOIN
+        };
+        let opened = open_join_accept(&phy, &keys, &wrong_nonce).unwrap();
+        assert_ne!(opened.received_mic, opened.computed_mic);
+    }
+
+    #[test]
+    fn lorawan_1_1_join_without_opt_neg() {
+        let keys = RootKeys::V1_1 {
+            nwk_key: key("2B7E151628AED2A6ABF7158809CF4F3C"),
+            app_key: key("B6B53F4A168A7A88BDF7EA135CE9CFCA"),
+        };
+        let (phy, _) = PhyPayload::from_input("203216BBBE84D45BB0B8ABF2C059F6914C").unwrap();
+        let opened = open_join_accept(&phy, &keys, &JOIN).unwrap();
+        assert!(!opened.accept.opt_neg());
+        assert_eq!(opened.received_mic, opened.computed_mic);
+        let session = derive_session_keys(&opened.accept, &keys, &JOIN);
+        assert_eq!(
+            session,
+            SessionKeys::V1_0 {
+                nwk_s_key: key("f6acc31a06b72b4d6b0d5e6226aac462"),
+                app_s_key: Some(key("be69ef63d9a704ac1f2c26f01274bccb")),
+            }
+        );
+
+        // Uplink from 26011BDA, FCnt 1, with a 1.0 MIC under that NwkSKey.
+        let (phy, _) = PhyPayload::from_input("40DA1B01260001000168690AF680D333").unwrap();
+        assert_eq!(
+            verify_mic(&phy, &session, &MicParams::default()),
+            Ok(Ok(()))
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! EUI-64s as used for DevEUIs, JoinEUIs and gateway IDs, with an offline
+//! table of IEEE prefixes bundled from `assets/eui_prefixes.csv`.
+
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+const PREFIXES_CSV: &str = include_str!("../assets/eui_prefixes.csv");
+
+/// Hex digits in an MA-L (OUI) prefix.
+const OUI_DIGITS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Eui64(pub u64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EuiError {
+    Empty,
+    /// Input contained something other than hex digits and separators.
+    NotHex(char),
+    /// Not 16 hex digits.
+    WrongLength(usize),
+}
+
+/// A block of EUIs assigned to one organisation or purpose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub prefix: u64,
+    /// Length of `prefix` in hex digits.
+    pub digits: u32,
+    pub name: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Record {
+    prefix: String,
+    name: String,
+}
+
+impl Eui64 {
+    /// Parse 16 hex digits, allowing `0x` and `-`, `:` or space separators.
+    pub fn new(input: &str) -> Result<Self, EuiError> {
+        let input = input.trim();
+        let input = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+            .unwrap_or(input);
+        let digits: String = input
+            .chars()
+            .filter(|ch| !matches!(ch, '-' | ':' | ' '))
+            .collect();
+        if digits.is_empty() {
+            return Err(EuiError::Empty);
+        }
+        if let Some(ch) = digits.chars().find(|ch| !ch.is_ascii_hexdigit()) {
+            return Err(EuiError::NotHex(ch));
+        }
+        if digits.len() != 16 {
+            return Err(EuiError::WrongLength(digits.len()));
+        }
+        // Exactly 16 hex digits, this can't fail.
+        Ok(Self(u64::from_str_radix(&digits, 16).unwrap_or_default()))
+    }
+
+    /// The same bytes the other way around, for EUIs shown LSB first.
+    pub fn reversed(&self) -> Self {
+        Self(self.0.swap_bytes())
+    }
+
+    pub fn oui(&self) -> u32 {
+        (self.0 >> 40) as u32
+    }
+
+    /// The U/L bit: set when the EUI was made up locally rather than
+    /// assigned from an IEEE block.
+    pub fn is_local(&self) -> bool {
+        (self.0 >> 56) & 0b10 != 0
+    }
+
+    /// The I/G bit: set for group (multicast) addresses.
+    pub fn is_group(&self) -> bool {
+        (self.0 >> 56) & 0b01 != 0
+    }
+
+    /// The MAC-48 this was built from by putting `FFFE` in the middle, as
+    /// many gateways do with their Ethernet address.
+    pub fn mac48(&self) -> Option<u64> {
+        ((self.0 >> 24) & 0xFFFF == 0xFFFE).then_some((self.0 >> 40) << 24 | self.0 & 0xFF_FFFF)
+    }
+
+    pub fn as_hex(&self) -> String {
+        format!("{:016X}", self.0)
+    }
+
+    pub fn as_dashed(&self) -> String {
+        let bytes: Vec<String> = self
+            .0
+            .to_be_bytes()
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect();
+        bytes.join("-")
+    }
+}
+
+impl Assignment {
+    pub fn contains(&self, eui: &Eui64) -> bool {
+        eui.0 >> (64 - 4 * self.digits) == self.prefix
+    }
+
+    pub fn prefix_hex(&self) -> String {
+        format!("{:0width$X}", self.prefix, width = self.digits as usize)
+    }
+}
+
+/// Every entry in the bundled table, parsed once.
+pub fn registry() -> &'static [Assignment] {
+    static REGISTRY: OnceLock<Vec<Assignment>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let (assignments, errors) = parse(PREFIXES_CSV);
+        debug_assert!(errors.is_empty(), "bad prefix rows: {errors:?}");
+        assignments
+    })
+}
+
+/// The vendor the EUI's MA-L (OUI) is assigned to.
+pub fn vendor(eui: &Eui64) -> Option<&'static Assignment> {
+    registry()
+        .iter()
+        .find(|a| a.digits == OUI_DIGITS && a.contains(eui))
+}
+
+/// The most specific block inside the OUI the EUI falls in, like a test
+/// range or a network's MA-S.
+pub fn known_range(eui: &Eui64) -> Option<&'static Assignment> {
+    registry()
+        .iter()
+        .filter(|a| a.digits > OUI_DIGITS && a.contains(eui))
+        .max_by_key(|a| a.digits)
+}
+
+/// EUIs with a meaning of their own rather than an owner.
+pub fn special(eui: &Eui64) -> Option<&'static str> {
+    match eui.0 {
+        0 => Some("all zeros, often left as the default JoinEUI"),
+        u64::MAX => Some("all ones, not a valid EUI"),
+        _ => None,
+    }
+}
+
+/// Only the reversed byte order has a known vendor, so the input was
+/// probably copied from somewhere showing it LSB first.
+pub fn looks_reversed(eui: &Eui64) -> bool {
+    vendor(eui).is_none() && vendor(&eui.reversed()).is_some()
+}
+
+fn parse(input: &str) -> (Vec<Assignment>, Vec<String>) {
+    let mut assignments = vec![];
+    let mut errors = vec![];
+    let mut reader = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+    for record in reader.deserialize::<Record>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(e.to_string());
+                continue;
+            }
+        };
+        let digits = record.prefix.len() as u32;
+        match u64::from_str_radix(&record.prefix, 16) {
+            Ok(prefix) if (OUI_DIGITS..16).contains(&digits) => assignments.push(Assignment {
+                prefix,
+                digits,
+                name: record.name,
+            }),
+            _ => errors.push(format!("{}: not a 6 to 15 hex digit prefix", record.prefix)),
+        }
+    }
+    (assignments, errors)
+}
+
+impl FromStr for Eui64 {
+    type Err = EuiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for Eui64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_hex())
+    }
+}
+
+impl fmt::Display for EuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty EUI"),
+            Self::NotHex(ch) => write!(f, "{ch:?} is not a hex digit"),
+            Self::WrongLength(len) => write!(f, "expected 16 hex digits, got {len}"),
+        }
+    }
+}
+
+impl std::error::Error for EuiError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_parses() {
+        let (assignments, errors) = parse(PREFIXES_CSV);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(assignments.len(), registry().len());
+    }
+
+    #[test]
+    fn parse_and_bits() {
+        let eui = Eui64::new("70-B3-D5-7E-D0-00-00-01").unwrap();
+        assert_eq!(eui, Eui64(0x70B3D57ED0000001));
+        assert_eq!(Eui64::new("0x70b3d57ed0000001"), Ok(eui));
+        assert_eq!(eui.reversed(), Eui64(0x010000D07ED5B370));
+        assert_eq!(eui.as_dashed(), "70-B3-D5-7E-D0-00-00-01");
+        assert!(!eui.is_local() && !eui.is_group());
+        assert!(Eui64(0x0200000000000000).is_local());
+        assert!(Eui64(0x0100000000000000).is_group());
+
+        assert_eq!(Eui64::new("70B3"), Err(EuiError::WrongLength(4)));
+        assert_eq!(Eui64::new("70B3D57ED000000G"), Err(EuiError::NotHex('G')));
+        assert_eq!(Eui64::new(" "), Err(EuiError::Empty));
+
+        assert_eq!(Eui64(0xB827EBFFFE123456).mac48(), Some(0xB827EB123456));
+        assert_eq!(eui.mac48(), None);
+    }
+
+    #[test]
+    fn lookups() {
+        let eui = Eui64(0x70B3D57ED0000001);
+        assert_eq!(
+            vendor(&eui).unwrap().name,
+            "IEEE Registration Authority (MA-S blocks)"
+        );
+        assert_eq!(known_range(&eui).unwrap().name, "The Things Network");
+        assert_eq!(known_range(&eui).unwrap().prefix_hex(), "70B3D57ED");
+
+        let doc = Eui64(0x00005EEF10000001);
+        assert_eq!(
+            known_range(&doc).unwrap().name,
+            "Documentation range (RFC 7042)"
+        );
+
+        let rn2483 = Eui64(0x0004A30B001C0530);
+        assert_eq!(vendor(&rn2483).unwrap().name, "Microchip Technology");
+        assert!(!looks_reversed(&rn2483));
+        assert!(looks_reversed(&rn2483.reversed()));
+        assert!(special(&Eui64(0)).is_some());
+    }
+}
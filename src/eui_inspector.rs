@@ -0,0 +1,94 @@
+use crate::eui::{self, Eui64};
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+}
+
+impl State {
+    pub fn main_view(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("EUI-64:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.input)
+                    .hint_text("DevEUI, JoinEUI or gateway ID")
+                    .desired_width(280.0),
+            );
+        });
+
+        if self.input.trim().is_empty() {
+            return;
+        }
+        let entered = match Eui64::new(&self.input) {
+            Ok(eui) => eui,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return;
+            }
+        };
+        if eui::looks_reversed(&entered) {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Only the reversed byte order has a known vendor, this was probably copied LSB first",
+            );
+        }
+
+        let orders = [entered, entered.reversed()];
+        egui::Grid::new("eui_grid").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.strong("As entered");
+            ui.strong("Reversed");
+            ui.end_row();
+
+            let mut row = |label: &str, value: &dyn Fn(&Eui64) -> String| {
+                ui.label(label);
+                for eui in &orders {
+                    ui.monospace(value(eui));
+                }
+                ui.end_row();
+            };
+            row("Hex:", &|eui| eui.as_hex());
+            row("Bytes:", &|eui| eui.as_dashed());
+            row("OUI:", &|eui| format!("{:06X}", eui.oui()));
+            row("Vendor:", &|eui| {
+                eui::vendor(eui)
+                    .map(|a| a.name.clone())
+                    .unwrap_or_else(|| "not in the bundled table".to_string())
+            });
+            row("Range:", &|eui| {
+                eui::special(eui)
+                    .map(str::to_string)
+                    .or_else(|| {
+                        eui::known_range(eui).map(|a| format!("{} ({})", a.name, a.prefix_hex()))
+                    })
+                    .unwrap_or_default()
+            });
+            row("U/L bit:", &|eui| {
+                if eui.is_local() {
+                    "1, locally administered".to_string()
+                } else {
+                    "0, globally unique".to_string()
+                }
+            });
+            row("I/G bit:", &|eui| {
+                if eui.is_group() {
+                    "1, group".to_string()
+                } else {
+                    "0, individual".to_string()
+                }
+            });
+            row("MAC-48:", &|eui| {
+                eui.mac48()
+                    .map(|mac| format!("{mac:012X}"))
+                    .unwrap_or_default()
+            });
+        });
+        ui.weak(format!(
+            "Vendors and ranges come from a bundled sample of {} IEEE prefixes common on LoRaWAN \
+             devices and gateways, not the full registry. A missing vendor doesn't mean the \
+             prefix is unassigned.",
+            eui::registry().len()
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+//! Semtech UDP packet forwarder protocol (GWMP) datagrams, as found in
+//! pcaps between gateways and network servers.
+
+use crate::{
+    eui::Eui64,
+    lorawan::{self, PhyPayload, PhyPayloadError},
+};
+use base64::Engine;
+use std::fmt;
+
+/// Version, token and identifier.
+const HEADER_LEN: usize = 4;
+const GATEWAY_EUI_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Identifier {
+    PushData,
+    PushAck,
+    PullData,
+    PullResp,
+    PullAck,
+    TxAck,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datagram {
+    /// 1 or 2, 2 added the token to PULL_RESP and JSON to TX_ACK.
+    pub version: u8,
+    /// Random, echoed back in the ACK.
+    pub token: u16,
+    pub identifier: Identifier,
+    /// Sent by the gateway in PUSH_DATA, PULL_DATA and TX_ACK.
+    pub gateway: Option<Eui64>,
+    /// The JSON body, or why it didn't parse.
+    pub json: Option<Result<serde_json::Value, String>>,
+}
+
+/// One `rxpk` or `txpk` object from the JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packet {
+    /// `rxpk` or `txpk`.
+    pub kind: &'static str,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// `data` decoded as a LoRaWAN frame.
+    pub phy: Option<Result<PhyPayload, PacketError>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GwmpError {
+    Input(PhyPayloadError),
+    TooShort {
+        identifier: Option<Identifier>,
+        len: usize,
+        min: usize,
+    },
+    UnknownIdentifier(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    NotBase64,
+    Frame(PhyPayloadError),
+}
+
+impl Identifier {
+    fn from_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0x00 => Self::PushData,
+            0x01 => Self::PushAck,
+            0x02 => Self::PullData,
+            0x03 => Self::PullResp,
+            0x04 => Self::PullAck,
+            0x05 => Self::TxAck,
+            _ => return None,
+        })
+    }
+
+    /// Sent by the gateway, which puts its EUI after the header.
+    pub fn is_upstream(&self) -> bool {
+        matches!(self, Self::PushData | Self::PullData | Self::TxAck)
+    }
+}
+
+impl Datagram {
+    /// Hex, base64 or a Wireshark, `xxd` or `hexdump -C` dump of a UDP payload.
+    pub fn from_input(input: &str) -> Result<Self, GwmpError> {
+        let bytes = match hex_dump_bytes(input) {
+            Some(bytes) => bytes,
+            None => lorawan::decode_input(input).map_err(GwmpError::Input)?.0,
+        };
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GwmpError> {
+        let len = bytes.len();
+        if len < HEADER_LEN {
+            return Err(GwmpError::TooShort {
+                identifier: None,
+                len,
+                min: HEADER_LEN,
+            });
+        }
+        let identifier =
+            Identifier::from_byte(bytes[3]).ok_or(GwmpError::UnknownIdentifier(bytes[3]))?;
+
+        let mut body = &bytes[HEADER_LEN..];
+        let mut gateway = None;
+        if identifier.is_upstream() {
+            let min = HEADER_LEN + GATEWAY_EUI_LEN;
+            if len < min {
+                return Err(GwmpError::TooShort {
+                    identifier: Some(identifier),
+                    len,
+                    min,
+                });
+            }
+            let eui = u64::from_be_bytes(body[..GATEWAY_EUI_LEN].try_into().unwrap_or_default());
+            gateway = Some(Eui64(eui));
+            body = &body[GATEWAY_EUI_LEN..];
+        }
+
+        let json = (!body.is_empty())
+            .then(|| serde_json::from_slice(body).map_err(|e| format!("JSON: {e}")));
+
+        Ok(Self {
+            version: bytes[0],
+            token: u16::from_be_bytes([bytes[1], bytes[2]]),
+            identifier,
+            gateway,
+            json,
+        })
+    }
+
+    /// Every `rxpk` and the `txpk` in the body.
+    pub fn packets(&self) -> Vec<Packet> {
+        let Some(Ok(json)) = &self.json else {
+            return vec![];
+        };
+        let rxpks = json["rxpk"].as_array().into_iter().flatten();
+        let txpk = json.get("txpk").into_iter();
+        rxpks
+            .map(|p| ("rxpk", p))
+            .chain(txpk.map(|p| ("txpk", p)))
+            .filter_map(|(kind, p)| {
+                let fields = p.as_object()?.clone();
+                let phy = fields.get("data").and_then(|d| d.as_str()).map(decode_data);
+                Some(Packet { kind, fields, phy })
+            })
+            .collect()
+    }
+
+    /// The body without the packets, `stat` and `txpk_ack` and the like.
+    pub fn other_fields(&self) -> serde_json::Map<String, serde_json::Value> {
+        let Some(Ok(serde_json::Value::Object(json))) = &self.json else {
+            return Default::default();
+        };
+        json.iter()
+            .filter(|(key, _)| !matches!(key.as_str(), "rxpk" | "txpk"))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+/// `data` is always base64, even when it only has hex digits.
+fn decode_data(data: &str) -> Result<PhyPayload, PacketError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| PacketError::NotBase64)?;
+    PhyPayload::from_bytes(&bytes).map_err(PacketError::Frame)
+}
+
+/// Bytes from a Wireshark, `xxd` or `hexdump -C` dump: lines of an offset,
+/// hex bytes and an optional ASCII column. `None` if the input doesn't look
+/// like that.
+fn hex_dump_bytes(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    for line in input.lines().filter(|l| !l.trim().is_empty()) {
+        let line = line.trim_start();
+        let (offset, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let offset = offset.strip_suffix(':').unwrap_or(offset);
+        // Every line starts where the last one stopped, which also keeps
+        // plain hex with spaces in it from reading as a dump.
+        if offset.len() < 4 || usize::from_str_radix(offset, 16).ok()? != bytes.len() {
+            return None;
+        }
+        // `hexdump -C` puts its ASCII column between bars.
+        let rest = rest.split('|').next().unwrap_or_default();
+        let line_bytes = dump_line_bytes(rest);
+        // `hexdump -C` ends with a line of just the offset.
+        if line_bytes.is_empty() && !rest.trim().is_empty() {
+            return None;
+        }
+        bytes.extend(line_bytes);
+    }
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+/// The bytes on one line of a dump, after the offset. Groups of any even
+/// number of hex digits are read until the rest of the line is the ASCII
+/// column for the bytes before it, so a short last line whose ASCII happens
+/// to look like hex isn't read as more bytes.
+fn dump_line_bytes(line: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    // Bytes so far and where the line continues after each group.
+    let mut groups = vec![];
+    let mut rest = line;
+    loop {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let Ok(group) = hex::decode(&trimmed[..end]) else {
+            break;
+        };
+        if group.is_empty() {
+            break;
+        }
+        bytes.extend(group);
+        rest = &trimmed[end..];
+        groups.push((bytes.len(), rest));
+    }
+    let ascii = |bytes: &[u8]| -> String {
+        bytes
+            .iter()
+            .map(|b| match b {
+                0x20..=0x7E => *b as char,
+                _ => '.',
+            })
+            .collect()
+    };
+    let len = groups
+        .iter()
+        .rev()
+        .find(|(len, rest)| rest.trim() == ascii(&bytes[..*len]).trim())
+        .map(|(len, _)| *len)
+        .unwrap_or(bytes.len());
+    bytes.truncate(len);
+    bytes
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PushData => "PUSH_DATA",
+            Self::PushAck => "PUSH_ACK",
+            Self::PullData => "PULL_DATA",
+            Self::PullResp => "PULL_RESP",
+            Self::PullAck => "PULL_ACK",
+            Self::TxAck => "TX_ACK",
+        })
+    }
+}
+
+impl fmt::Display for GwmpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(e) => e.fmt(f),
+            Self::TooShort {
+                identifier: Some(identifier),
+                len,
+                min,
+            } => write!(f, "{identifier} needs at least {min} bytes, got {len}"),
+            Self::TooShort {
+                identifier: None,
+                len,
+                min,
+            } => write!(f, "needs at least {min} bytes for the header, got {len}"),
+            Self::UnknownIdentifier(id) => write!(f, "unknown identifier {id:#04X}"),
+        }
+    }
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBase64 => write!(f, "data is not base64"),
+            Self::Frame(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for GwmpError {}
+
+impl std::error::Error for PacketError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_data(json: &str) -> Vec<u8> {
+        let mut bytes = vec![0x02, 0xAB, 0xCD, 0x00];
+        bytes.extend(0xAA555A0000000101u64.to_be_bytes());
+        bytes.extend(json.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn push_data_rxpk() {
+        let json = r#"{"rxpk":[{"tmst":123,"freq":868.1,"datr":"SF7BW125","data":"QNobASaBAQACAcWqC7DM"},{"data":"!!"}],"stat":{"rxnb":2}}"#;
+        let datagram = Datagram::from_bytes(&push_data(json)).unwrap();
+        assert_eq!(datagram.version, 2);
+        assert_eq!(datagram.token, 0xABCD);
+        assert_eq!(datagram.identifier, Identifier::PushData);
+        assert_eq!(datagram.gateway, Some(Eui64(0xAA555A0000000101)));
+
+        let packets = datagram.packets();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].kind, "rxpk");
+        assert_eq!(packets[0].fields["datr"], "SF7BW125");
+        let phy = packets[0].phy.clone().unwrap().unwrap();
+        let lorawan::MacPayload::Data(data) = phy.payload else {
+            panic!("not data: {:?}", phy.payload);
+        };
+        assert_eq!(data.fhdr.dev_addr, 0x26011BDA);
+        assert_eq!(packets[1].phy, Some(Err(PacketError::NotBase64)));
+        assert!(datagram.other_fields().contains_key("stat"));
+    }
+
+    #[test]
+    fn other_datagrams() {
+        let pull_data = Datagram::from_input("01 1234 02 AA555A0000000101").unwrap();
+        assert_eq!(pull_data.identifier, Identifier::PullData);
+        assert_eq!(pull_data.json, None);
+
+        let ack = Datagram::from_input("02123401").unwrap();
+        assert_eq!(ack.identifier, Identifier::PushAck);
+        assert_eq!(ack.gateway, None);
+
+        let mut pull_resp = vec![0x02, 0x00, 0x01, 0x03];
+        pull_resp.extend(br#"{"txpk":{"imme":true,"data":"QNobASaBAQACAcWqC7DM"}}"#);
+        let pull_resp = Datagram::from_bytes(&pull_resp).unwrap();
+        assert_eq!(pull_resp.packets()[0].kind, "txpk");
+
+        let broken = Datagram::from_bytes(&push_data("{\"rxpk\":")).unwrap();
+        assert!(matches!(broken.json, Some(Err(_))));
+
+        assert_eq!(
+            Datagram::from_input("02123402AA55"),
+            Err(GwmpError::TooShort {
+                identifier: Some(Identifier::PullData),
+                len: 6,
+                min: 12
+            })
+        );
+        assert_eq!(
+            Datagram::from_input("02123409"),
+            Err(GwmpError::UnknownIdentifier(9))
+        );
+    }
+
+    #[test]
+    fn wireshark_hex_dump() {
+        let dump = "0000   02 12 34 02 aa 55 5a 00 00 00 01 01   .4..UZ....\n";
+        let datagram = Datagram::from_input(dump).unwrap();
+        assert_eq!(datagram.identifier, Identifier::PullData);
+        assert_eq!(datagram.gateway, Some(Eui64(0xAA555A0000000101)));
+    }
+
+    /// PUSH_DATA from AA555A0000000101 with `{"x":"01"}` as the body.
+    const DUMPED: &str = "021234 00 AA555A0000000101 7B2278223A22303122 7D";
+
+    fn dumped() -> Vec<u8> {
+        hex::decode(DUMPED.replace(' ', "")).unwrap()
+    }
+
+    #[test]
+    fn xxd_dump() {
+        let dump = "\
+00000000: 0212 3400 aa55 5a00 0000 0101 7b22 7822  ..4..UZ.....{\"x\"
+00000010: 3a22 3031 227d                           :\"01\"}
+";
+        assert_eq!(hex_dump_bytes(dump), Some(dumped()));
+        let datagram = Datagram::from_input(dump).unwrap();
+        assert_eq!(datagram.json, Some(Ok(serde_json::json!({"x": "01"}))));
+    }
+
+    #[test]
+    fn hexdump_c_dump() {
+        let dump = "\
+00000000  02 12 34 00 aa 55 5a 00  00 00 01 01 7b 22 78 22  |..4..UZ.....{\"x\"|
+00000010  3a 22 30 31 22 7d                                 |:\"01\"}|
+00000016
+";
+        assert_eq!(hex_dump_bytes(dump), Some(dumped()));
+
+        // Wireshark's ASCII column has no bars, and "01" in it is not a byte.
+        let wireshark = "\
+0000   02 12 34 00 aa 55 5a 00 00 00 01 01 7b 22 78 22   ..4..UZ.....{\"x\"
+0010   3a 22 30 31 22 7d                                 :\"01\"}
+";
+        assert_eq!(hex_dump_bytes(wireshark), Some(dumped()));
+
+        // Plain hex with spaces is not a dump.
+        assert_eq!(hex_dump_bytes("0212 3401"), None);
+    }
+}
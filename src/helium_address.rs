@@ -0,0 +1,192 @@
+use crate::roaming_token::{Pubkey, TokenError};
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+}
+
+/// One gateway in all the forms it goes by.
+#[derive(Debug)]
+struct Converted {
+    b58: String,
+    animal_name: String,
+    pubkeybin: Vec<u8>,
+    pubkey: Result<Pubkey, TokenError>,
+}
+
+impl State {
+    /// Animal names are resolved through `known_gateways`, and new gateways
+    /// can be added to it.
+    pub fn main_view(&mut self, ui: &mut egui::Ui, known_gateways: &mut Vec<String>) {
+        ui.label("b58 addresses, animal names, or pubkeybin hex, one per line:");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.input)
+                .hint_text("112qB3YaH5bZkCnKA5uRH7tBtGNv2Y5B4smv1jsmvGUzgKT71QpE\nangry purple tiger\n0x0072C4AE...")
+                .desired_rows(3),
+        );
+
+        let lines: Vec<_> = self
+            .input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+
+        egui::Grid::new("helium_address_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                for text in ["Input", "Animal Name", "b58", "Pubkeybin", "Key", ""] {
+                    ui.strong(text);
+                }
+                ui.end_row();
+
+                for line in lines {
+                    ui.label(line);
+                    match convert(line, known_gateways) {
+                        Ok(c) => {
+                            ui.label(&c.animal_name);
+                            ui.monospace(&c.b58);
+                            ui.monospace(hex::encode_upper(&c.pubkeybin));
+                            match &c.pubkey {
+                                Ok(pubkey) if pubkey.length_mismatch() => {
+                                    ui.colored_label(
+                                        ui.visuals().warn_fg_color,
+                                        format!(
+                                            "{} {}, {} byte key",
+                                            pubkey.network,
+                                            pubkey.key_type,
+                                            pubkey.key.len()
+                                        ),
+                                    );
+                                }
+                                Ok(pubkey) => {
+                                    ui.label(format!("{} {}", pubkey.network, pubkey.key_type));
+                                }
+                                Err(e) => {
+                                    ui.colored_label(ui.visuals().warn_fg_color, e.to_string());
+                                }
+                            }
+                            if known_gateways.contains(&c.b58) {
+                                ui.label("");
+                            } else if ui.button("Remember").clicked() {
+                                known_gateways.push(c.b58);
+                            }
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+/// Work out which of the three forms `input` is and fill in the others.
+fn convert(input: &str, known_gateways: &[String]) -> Result<Converted, String> {
+    let b58_error = match from_b58(input) {
+        Ok(pubkeybin) => return Ok(Converted::new(pubkeybin)),
+        Err(e) => e,
+    };
+
+    let hex_input = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+    if let Ok(pubkeybin) = hex::decode(hex_input) {
+        return Ok(Converted::new(pubkeybin));
+    }
+
+    if input.contains([' ', '-']) {
+        let b58 = find_by_animal_name(input, known_gateways)
+            .ok_or_else(|| format!("{input:?} is not the animal name of a known gateway"))?;
+        return from_b58(b58)
+            .map(Converted::new)
+            .map_err(|e| format!("{b58}: {e}"));
+    }
+
+    Err(format!("not hex, or a b58 address: {b58_error}"))
+}
+
+impl Converted {
+    fn new(pubkeybin: Vec<u8>) -> Self {
+        let b58 = to_b58(&pubkeybin);
+        Self {
+            animal_name: animal_name(&b58),
+            pubkey: Pubkey::from_bin(&pubkeybin),
+            b58,
+            pubkeybin,
+        }
+    }
+}
+
+/// Base58check with a 0 version byte, the way helium-crypto displays a
+/// PubkeyBinary.
+pub fn to_b58(pubkeybin: &[u8]) -> String {
+    let mut versioned = vec![0u8; pubkeybin.len() + 1];
+    versioned[1..].copy_from_slice(pubkeybin);
+    bs58::encode(&versioned).with_check().into_string()
+}
+
+/// The pubkeybin in a b58 address, checking the checksum and version byte.
+pub fn from_b58(b58: &str) -> Result<Vec<u8>, bs58::decode::Error> {
+    let mut versioned = bs58::decode(b58.trim()).with_check(Some(0)).into_vec()?;
+    versioned.remove(0);
+    Ok(versioned)
+}
+
+pub fn animal_name(b58: &str) -> String {
+    match b58.parse::<angry_purple_tiger::AnimalName>() {
+        Ok(name) => name.to_string(),
+        Err(never) => match never {},
+    }
+}
+
+/// Animal names can't be reversed, so look for a known gateway with this one.
+/// Case, spaces, and dashes don't matter.
+pub fn find_by_animal_name<'a>(name: &str, known_gateways: &'a [String]) -> Option<&'a String> {
+    let wanted = name
+        .split(|ch: char| ch.is_whitespace() || ch == '-' || ch == '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    known_gateways.iter().find(|b58| animal_name(b58) == wanted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const B58: &str = "112qB3YaH5bZkCnKA5uRH7tBtGNv2Y5B4smv1jsmvGUzgKT71QpE";
+
+    #[test]
+    fn convert_each_form() {
+        let from_b58 = convert(B58, &[]).unwrap();
+        assert_eq!(from_b58.b58, B58);
+        assert_eq!(from_b58.pubkeybin.len(), 33);
+        assert!(from_b58.pubkey.is_ok());
+
+        let hex = format!("0x{}", hex::encode(&from_b58.pubkeybin));
+        let from_hex = convert(&hex, &[]).unwrap();
+        assert_eq!(from_hex.b58, B58);
+
+        let known = vec![B58.to_string()];
+        let name = from_b58.animal_name.replace('-', " ").to_uppercase();
+        let from_name = convert(&name, &known).unwrap();
+        assert_eq!(from_name.b58, B58);
+        assert!(convert(&name, &[]).is_err());
+    }
+
+    #[test]
+    fn bad_checksum() {
+        // Last character changed.
+        let tampered = format!("{}R", &B58[..B58.len() - 1]);
+        let e = convert(&tampered, &[]).unwrap_err();
+        assert!(e.contains("checksum"), "{e}");
+    }
+}
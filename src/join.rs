@@ -0,0 +1,309 @@
+use crate::{
+    addressing::{Devaddr, NetID},
+    crypto::{self, JoinParams, RootKeys, SessionKeys},
+    eui::Eui64,
+    lorawan::{JoinAccept, PhyPayload},
+    operators,
+    phy_payload::Version,
+};
+use rand::Rng;
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    version: Version,
+    join_eui: String,
+    dev_eui: String,
+    dev_nonce: u16,
+    /// Keys are never saved.
+    #[serde(skip)]
+    app_key: String,
+    #[serde(skip)]
+    nwk_key: String,
+    join_accept: String,
+}
+
+impl State {
+    /// Returns session keys to check frames with in the PHYPayload view.
+    pub fn main_view(&mut self, ui: &mut egui::Ui) -> Option<SessionKeys> {
+        let Self {
+            version,
+            join_eui,
+            dev_eui,
+            dev_nonce,
+            app_key,
+            nwk_key,
+            join_accept,
+        } = self;
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(version, Version::V1_0, "LoRaWAN 1.0.x");
+            ui.selectable_value(version, Version::V1_1, "LoRaWAN 1.1");
+        });
+
+        egui::Grid::new("join_inputs").show(ui, |ui| {
+            let field = |ui: &mut egui::Ui, label: &str, value: &mut String, hint: &str| {
+                ui.label(label);
+                ui.add(
+                    egui::TextEdit::singleline(value)
+                        .hint_text(hint)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(280.0),
+                );
+                ui.end_row();
+            };
+            let join_eui_label = match version {
+                Version::V1_0 => "AppEUI:",
+                Version::V1_1 => "JoinEUI:",
+            };
+            field(ui, join_eui_label, join_eui, "16 hex digits");
+            field(ui, "DevEUI:", dev_eui, "16 hex digits");
+
+            ui.label("DevNonce:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(dev_nonce).hexadecimal(4, false, true));
+                if ui.button("Random").clicked() {
+                    *dev_nonce = rand::thread_rng().gen();
+                }
+            });
+            ui.end_row();
+
+            field(ui, "AppKey:", app_key, "32 hex digits");
+            if *version == Version::V1_1 {
+                field(ui, "NwkKey:", nwk_key, "32 hex digits");
+            }
+        });
+
+        let params = match (Eui64::new(join_eui), Eui64::new(dev_eui)) {
+            (Ok(join_eui), Ok(dev_eui)) => JoinParams {
+                join_eui: join_eui.0,
+                dev_eui: dev_eui.0,
+                dev_nonce: *dev_nonce,
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                ui.weak(e.to_string());
+                return None;
+            }
+        };
+        let keys = match root_keys(*version, app_key, nwk_key) {
+            Ok(keys) => keys,
+            Err(e) => {
+                ui.weak(e.to_string());
+                return None;
+            }
+        };
+
+        ui.separator();
+        let join_request = hex::encode_upper(crypto::build_join_request(&params, &keys));
+        ui.horizontal(|ui| {
+            ui.label("Join-Request:");
+            ui.monospace(&join_request);
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = join_request.clone());
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Join-Accept:");
+            ui.add(
+                egui::TextEdit::singleline(join_accept)
+                    .hint_text("hex or base64 PHYPayload")
+                    .desired_width(f32::INFINITY),
+            );
+        });
+        if join_accept.trim().is_empty() {
+            return None;
+        }
+        let opened = match PhyPayload::from_input(join_accept)
+            .map_err(crypto::CryptoError::Frame)
+            .and_then(|(phy, _)| crypto::open_join_accept(&phy, &keys, &params))
+        {
+            Ok(opened) => opened,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+
+        let accept = &opened.accept;
+        if opened.received_mic != opened.computed_mic {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!(
+                    "MIC {} doesn't match {}, check the keys, the EUIs and the DevNonce",
+                    hex::encode_upper(opened.received_mic),
+                    hex::encode_upper(opened.computed_mic)
+                ),
+            );
+        }
+        if accept.opt_neg() && *version == Version::V1_0 {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "OptNeg is set, the join server expects a LoRaWAN 1.1 device",
+            );
+        }
+        show_join_accept(ui, accept);
+
+        ui.separator();
+        let session_keys = crypto::derive_session_keys(accept, &keys, &params);
+        egui::Grid::new("derived_keys")
+            .striped(true)
+            .show(ui, |ui| {
+                let mut key = |label: &str, key: Option<crypto::Key>| {
+                    ui.label(label);
+                    ui.monospace(key.map(hex::encode_upper).unwrap_or_default());
+                    ui.end_row();
+                };
+                match session_keys {
+                    SessionKeys::V1_0 {
+                        nwk_s_key,
+                        app_s_key,
+                    } => {
+                        key("NwkSKey:", Some(nwk_s_key));
+                        key("AppSKey:", app_s_key);
+                    }
+                    SessionKeys::V1_1 {
+                        f_nwk_s_int_key,
+                        s_nwk_s_int_key,
+                        nwk_s_enc_key,
+                        app_s_key,
+                    } => {
+                        key("FNwkSIntKey:", Some(f_nwk_s_int_key));
+                        key("SNwkSIntKey:", Some(s_nwk_s_int_key));
+                        key("NwkSEncKey:", Some(nwk_s_enc_key));
+                        key("AppSKey:", app_s_key);
+                    }
+                }
+            });
+        ui.button("Check frames with these keys")
+            .on_hover_text("Open the PHYPayload view with the session keys filled in")
+            .clicked()
+            .then_some(session_keys)
+    }
+}
+
+fn show_join_accept(ui: &mut egui::Ui, accept: &JoinAccept) {
+    egui::Grid::new("join_accept").striped(true).show(ui, |ui| {
+        ui.label("JoinNonce:");
+        ui.monospace(format!("{:06X}", accept.join_nonce));
+        ui.end_row();
+
+        ui.label("NetID:");
+        ui.monospace(format!("{:06X}", accept.net_id));
+        ui.end_row();
+
+        ui.label("DevAddr:");
+        ui.monospace(format!("{:08X}", accept.dev_addr));
+        match check_addressing(accept) {
+            Ok(summary) => ui.label(summary),
+            Err(problem) => ui.colored_label(ui.visuals().error_fg_color, problem),
+        };
+        ui.end_row();
+
+        ui.label("DLSettings:");
+        ui.monospace(format!("{:02X}", accept.dl_settings));
+        ui.label(format!(
+            "RX1DROffset {}, RX2 DR{}, OptNeg {}",
+            accept.rx1_dr_offset(),
+            accept.rx2_data_rate(),
+            if accept.opt_neg() { "set" } else { "unset" }
+        ));
+        ui.end_row();
+
+        ui.label("RxDelay:");
+        // 0 means the same as 1.
+        ui.label(format!("{} s", (accept.rx_delay & 0x0F).max(1)));
+        ui.end_row();
+
+        ui.label("CFList:");
+        match accept.cf_list {
+            Some(cf_list) => {
+                ui.monospace(hex::encode_upper(cf_list));
+                ui.label(describe_cf_list(&cf_list));
+            }
+            None => {
+                ui.weak("None");
+            }
+        }
+        ui.end_row();
+    });
+}
+
+/// Check the DevAddr was handed out from the NetID's own range.
+fn check_addressing(accept: &JoinAccept) -> Result<String, String> {
+    let net_id = NetID::try_from(accept.net_id).map_err(|e| format!("NetID: {e}"))?;
+    let devaddr = Devaddr::try_from(accept.dev_addr).map_err(|e| format!("DevAddr: {e}"))?;
+    if !net_id.contains(&devaddr) {
+        return Err(format!(
+            "DevAddr belongs to NetID {}, not {net_id}",
+            devaddr.net_id()
+        ));
+    }
+    let operator = operators::lookup(&net_id)
+        .map(|op| format!(", {}", op.name))
+        .unwrap_or_default();
+    Ok(format!("in NetID {net_id}{operator}"))
+}
+
+/// CFList type 0 is five extra channel frequencies, type 1 channel masks.
+fn describe_cf_list(cf_list: &[u8; 16]) -> String {
+    match cf_list[15] {
+        0 => {
+            let freqs: Vec<String> = cf_list[..15]
+                .chunks(3)
+                .map(|f| u32::from_le_bytes([f[0], f[1], f[2], 0]))
+                .filter(|f| *f != 0)
+                .map(|f| format!("{:.1}", f as f64 / 10_000.0))
+                .collect();
+            format!("frequencies {} MHz", freqs.join(", "))
+        }
+        1 => "channel masks".to_string(),
+        t => format!("type {t} (RFU)"),
+    }
+}
+
+fn root_keys(
+    version: Version,
+    app_key: &str,
+    nwk_key: &str,
+) -> Result<RootKeys, crypto::CryptoError> {
+    let app_key = crypto::parse_key(app_key)?;
+    Ok(match version {
+        Version::V1_0 => RootKeys::V1_0 { app_key },
+        Version::V1_1 => RootKeys::V1_1 {
+            nwk_key: crypto::parse_key(nwk_key)?,
+            app_key,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addressing_checks() {
+        let mut accept = JoinAccept::from_bytes(&[0; 12]).unwrap();
+        accept.net_id = 0x000013;
+        accept.dev_addr = 0x26011BDA;
+        assert!(check_addressing(&accept)
+            .unwrap()
+            .contains("The Things Network"));
+
+        accept.dev_addr = 0x48000001;
+        assert_eq!(
+            check_addressing(&accept),
+            Err("DevAddr belongs to NetID 000024, not 000013".to_string())
+        );
+    }
+
+    #[test]
+    fn cf_list_frequencies() {
+        let mut cf_list = [0u8; 16];
+        // 867.1 and 867.3 MHz in units of 100 Hz.
+        cf_list[..3].copy_from_slice(&8_671_000u32.to_le_bytes()[..3]);
+        cf_list[3..6].copy_from_slice(&8_673_000u32.to_le_bytes()[..3]);
+        assert_eq!(describe_cf_list(&cf_list), "frequencies 867.1, 867.3 MHz");
+    }
+}
@@ -1,7 +1,45 @@
+pub mod addressing;
+pub mod airtime;
+#[cfg(feature = "gui")]
+mod airtime_calculator;
+#[cfg(feature = "gui")]
 mod app;
+#[cfg(feature = "gui")]
+mod backend_interfaces;
+#[cfg(feature = "gui")]
+mod batch;
+pub mod cli;
+#[cfg(feature = "gui")]
+mod compose;
+pub mod crypto;
+pub mod eui;
+#[cfg(feature = "gui")]
+mod eui_inspector;
 // mod bit_looker;
+pub mod gwmp;
+#[cfg(feature = "gui")]
+mod helium_address;
+#[cfg(feature = "gui")]
+mod join;
+pub mod lorawan;
+#[cfg(feature = "gui")]
 mod net_id;
 mod num_format;
+pub mod operators;
+#[cfg(feature = "gui")]
+mod packet_forwarder;
+#[cfg(feature = "gui")]
+mod phy_payload;
+#[cfg(feature = "gui")]
+mod planner;
+#[cfg(feature = "gui")]
+mod region;
+#[cfg(feature = "gui")]
 mod roaming_token;
+#[cfg(feature = "gui")]
+mod subnet;
+#[cfg(feature = "gui")]
+mod token_history;
 // mod santa;
+#[cfg(feature = "gui")]
 pub use app::App;
@@ -0,0 +1,591 @@
+//! LoRaWAN PHYPayload framing.
+//!
+//! Like [`crate::addressing`] nothing in here knows about the UI. Multi-byte
+//! fields are little endian on air, they're stored here as plain numbers and
+//! shown most significant byte first like every other LoRaWAN tool.
+use crate::addressing::{DevAddrError, Devaddr};
+use base64::Engine;
+use std::fmt;
+
+pub const MIC_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MType {
+    JoinRequest,
+    JoinAccept,
+    UnconfirmedDataUp,
+    UnconfirmedDataDown,
+    ConfirmedDataUp,
+    ConfirmedDataDown,
+    RejoinRequest,
+    Proprietary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mhdr {
+    pub mtype: MType,
+    /// 0 is LoRaWAN R1, the rest are RFU.
+    pub major: u8,
+    /// Bits 2-4, always 0 so far.
+    pub rfu: u8,
+}
+
+/// Frame control, whose bits 4 and 6 mean different things up and down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FCtrl {
+    pub byte: u8,
+    pub uplink: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fhdr {
+    /// Kept raw, Devaddrs starting with `FF` don't belong to any NetID but
+    /// still show up on air.
+    pub dev_addr: u32,
+    pub fctrl: FCtrl,
+    pub fcnt: u16,
+    pub fopts: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataPayload {
+    pub fhdr: Fhdr,
+    pub fport: Option<u8>,
+    pub frm_payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinRequest {
+    pub join_eui: u64,
+    pub dev_eui: u64,
+    pub dev_nonce: u16,
+}
+
+/// The fields of a Join-Accept once it's been decrypted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinAccept {
+    pub join_nonce: u32,
+    pub net_id: u32,
+    pub dev_addr: u32,
+    pub dl_settings: u8,
+    pub rx_delay: u8,
+    pub cf_list: Option<[u8; 16]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacPayload {
+    JoinRequest(JoinRequest),
+    /// Join-Accepts are encrypted with the device's key, this is everything
+    /// between the MHDR and the end of the frame, including the MIC.
+    JoinAccept(Vec<u8>),
+    Data(DataPayload),
+    RejoinRequest(Vec<u8>),
+    Proprietary(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhyPayload {
+    pub mhdr: Mhdr,
+    pub payload: MacPayload,
+    /// `None` for Join-Accepts, the MIC is encrypted along with the rest.
+    pub mic: Option<[u8; MIC_LEN]>,
+    /// The whole frame, for anything that needs to check the MIC.
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhyPayloadError {
+    Empty,
+    /// Neither hex nor standard base64.
+    NotHexOrBase64,
+    /// Not the encoding that was asked for.
+    NotEncoded(Encoding),
+    /// Shorter than the smallest frame of its MType.
+    TooShort {
+        mtype: MType,
+        len: usize,
+        min: usize,
+    },
+    /// A Join-Request is always the same length.
+    WrongLength {
+        mtype: MType,
+        len: usize,
+        expected: &'static [usize],
+    },
+    /// FCtrl claims more FOpts than the frame holds.
+    FOptsTooLong {
+        fopts_len: usize,
+        available: usize,
+    },
+}
+
+/// How the input text was decoded to bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Encoding {
+    Hex,
+    Base64,
+}
+
+impl MType {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b111 {
+            0 => Self::JoinRequest,
+            1 => Self::JoinAccept,
+            2 => Self::UnconfirmedDataUp,
+            3 => Self::UnconfirmedDataDown,
+            4 => Self::ConfirmedDataUp,
+            5 => Self::ConfirmedDataDown,
+            6 => Self::RejoinRequest,
+            _ => Self::Proprietary,
+        }
+    }
+
+    pub fn is_uplink(&self) -> bool {
+        matches!(
+            self,
+            Self::JoinRequest
+                | Self::UnconfirmedDataUp
+                | Self::ConfirmedDataUp
+                | Self::RejoinRequest
+        )
+    }
+
+    pub fn is_data(&self) -> bool {
+        matches!(
+            self,
+            Self::UnconfirmedDataUp
+                | Self::UnconfirmedDataDown
+                | Self::ConfirmedDataUp
+                | Self::ConfirmedDataDown
+        )
+    }
+}
+
+impl Mhdr {
+    pub fn from_byte(byte: u8) -> Self {
+        Self {
+            mtype: MType::from_bits(byte >> 5),
+            major: byte & 0b11,
+            rfu: (byte >> 2) & 0b111,
+        }
+    }
+}
+
+impl FCtrl {
+    pub fn adr(&self) -> bool {
+        self.byte & 0x80 != 0
+    }
+
+    /// Uplink only.
+    pub fn adr_ack_req(&self) -> bool {
+        self.uplink && self.byte & 0x40 != 0
+    }
+
+    pub fn ack(&self) -> bool {
+        self.byte & 0x20 != 0
+    }
+
+    /// Uplink only.
+    pub fn class_b(&self) -> bool {
+        self.uplink && self.byte & 0x10 != 0
+    }
+
+    /// Downlink only.
+    pub fn f_pending(&self) -> bool {
+        !self.uplink && self.byte & 0x10 != 0
+    }
+
+    pub fn f_opts_len(&self) -> usize {
+        (self.byte & 0x0F) as usize
+    }
+}
+
+impl Fhdr {
+    pub fn devaddr(&self) -> Result<Devaddr, DevAddrError> {
+        Devaddr::try_from(self.dev_addr)
+    }
+}
+
+impl PhyPayload {
+    /// Hex (spaces and a `0x` prefix are fine) or standard base64, as
+    /// packet forwarders and LNS logs print them.
+    pub fn from_input(input: &str) -> Result<(Self, Encoding), PhyPayloadError> {
+        Self::from_input_as(input, None)
+    }
+
+    /// Like [`PhyPayload::from_input`], but only as `encoding` if given.
+    pub fn from_input_as(
+        input: &str,
+        encoding: Option<Encoding>,
+    ) -> Result<(Self, Encoding), PhyPayloadError> {
+        let (bytes, encoding) = decode_input_as(input, encoding)?;
+        Ok((Self::from_bytes(&bytes)?, encoding))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PhyPayloadError> {
+        let Some(&mhdr_byte) = bytes.first() else {
+            return Err(PhyPayloadError::Empty);
+        };
+        let mhdr = Mhdr::from_byte(mhdr_byte);
+        let mtype = mhdr.mtype;
+        let len = bytes.len();
+        let too_short = |min| PhyPayloadError::TooShort { mtype, len, min };
+
+        if mtype == MType::JoinAccept {
+            const LENGTHS: &[usize] = &[17, 33];
+            if !LENGTHS.contains(&len) {
+                return Err(PhyPayloadError::WrongLength {
+                    mtype,
+                    len,
+                    expected: LENGTHS,
+                });
+            }
+            return Ok(Self {
+                mhdr,
+                payload: MacPayload::JoinAccept(bytes[1..].to_vec()),
+                mic: None,
+                bytes: bytes.to_vec(),
+            });
+        }
+
+        if len < 1 + MIC_LEN {
+            return Err(too_short(1 + MIC_LEN));
+        }
+        let mac = &bytes[1..len - MIC_LEN];
+        let mic = bytes[len - MIC_LEN..].try_into().ok();
+
+        let payload = match mtype {
+            MType::JoinRequest => {
+                const LENGTHS: &[usize] = &[23];
+                if !LENGTHS.contains(&len) {
+                    return Err(PhyPayloadError::WrongLength {
+                        mtype,
+                        len,
+                        expected: LENGTHS,
+                    });
+                }
+                MacPayload::JoinRequest(JoinRequest {
+                    join_eui: u64_le(&mac[0..8]),
+                    dev_eui: u64_le(&mac[8..16]),
+                    dev_nonce: u16::from_le_bytes([mac[16], mac[17]]),
+                })
+            }
+            MType::RejoinRequest => {
+                const LENGTHS: &[usize] = &[19, 24];
+                if !LENGTHS.contains(&len) {
+                    return Err(PhyPayloadError::WrongLength {
+                        mtype,
+                        len,
+                        expected: LENGTHS,
+                    });
+                }
+                MacPayload::RejoinRequest(mac.to_vec())
+            }
+            MType::Proprietary => MacPayload::Proprietary(mac.to_vec()),
+            _ => {
+                // DevAddr, FCtrl, FCnt
+                if mac.len() < 7 {
+                    return Err(too_short(1 + 7 + MIC_LEN));
+                }
+                let fctrl = FCtrl {
+                    byte: mac[4],
+                    uplink: mtype.is_uplink(),
+                };
+                let fopts_end = 7 + fctrl.f_opts_len();
+                if fopts_end > mac.len() {
+                    return Err(PhyPayloadError::FOptsTooLong {
+                        fopts_len: fctrl.f_opts_len(),
+                        available: mac.len() - 7,
+                    });
+                }
+                let rest = &mac[fopts_end..];
+                MacPayload::Data(DataPayload {
+                    fhdr: Fhdr {
+                        dev_addr: u32::from_le_bytes([mac[0], mac[1], mac[2], mac[3]]),
+                        fctrl,
+                        fcnt: u16::from_le_bytes([mac[5], mac[6]]),
+                        fopts: mac[7..fopts_end].to_vec(),
+                    },
+                    fport: rest.first().copied(),
+                    frm_payload: rest.get(1..).unwrap_or_default().to_vec(),
+                })
+            }
+        };
+
+        Ok(Self {
+            mhdr,
+            payload,
+            mic,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Things that parse but break the spec.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        if self.mhdr.major != 0 {
+            warnings.push(format!("major version {} is RFU", self.mhdr.major));
+        }
+        if self.mhdr.rfu != 0 {
+            warnings.push("MHDR RFU bits are set".to_string());
+        }
+        if let MacPayload::Data(data) = &self.payload {
+            if data.fport == Some(0) && !data.fhdr.fopts.is_empty() {
+                warnings.push("FOpts must be empty when FPort is 0".to_string());
+            }
+            if let (Some(fport), true) = (data.fport, data.frm_payload.is_empty()) {
+                warnings.push(format!(
+                    "FPort {fport} with an empty FRMPayload, FPort should be left out"
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+impl JoinAccept {
+    /// `bytes` is a decrypted Join-Accept without the MHDR or MIC.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PhyPayloadError> {
+        let mtype = MType::JoinAccept;
+        if bytes.len() != 12 && bytes.len() != 28 {
+            return Err(PhyPayloadError::WrongLength {
+                mtype,
+                len: bytes.len() + 1 + MIC_LEN,
+                expected: &[17, 33],
+            });
+        }
+        Ok(Self {
+            join_nonce: u24_le(&bytes[0..3]),
+            net_id: u24_le(&bytes[3..6]),
+            dev_addr: u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
+            dl_settings: bytes[10],
+            rx_delay: bytes[11],
+            cf_list: bytes.get(12..28).and_then(|cf| cf.try_into().ok()),
+        })
+    }
+
+    pub fn rx1_dr_offset(&self) -> u8 {
+        (self.dl_settings >> 4) & 0b111
+    }
+
+    pub fn rx2_data_rate(&self) -> u8 {
+        self.dl_settings & 0x0F
+    }
+
+    /// Set by 1.1 join servers, which changes how session keys are derived.
+    pub fn opt_neg(&self) -> bool {
+        self.dl_settings & 0x80 != 0
+    }
+}
+
+/// Hex if it can be, otherwise base64. Some text is both, like `AAAA`, use
+/// [`decode_input_as`] when the encoding is known.
+pub fn decode_input(input: &str) -> Result<(Vec<u8>, Encoding), PhyPayloadError> {
+    decode_input_as(input, None)
+}
+
+/// Decode `input` as `encoding`, or guess like [`decode_input`] for `None`.
+pub fn decode_input_as(
+    input: &str,
+    encoding: Option<Encoding>,
+) -> Result<(Vec<u8>, Encoding), PhyPayloadError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(PhyPayloadError::Empty);
+    }
+    let as_hex = || {
+        let hex: String = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+            .unwrap_or(input)
+            .split_whitespace()
+            .collect();
+        hex::decode(hex).ok()
+    };
+    let as_base64 = || base64::engine::general_purpose::STANDARD.decode(input).ok();
+    let Some(encoding) = encoding else {
+        return as_hex()
+            .map(|bytes| (bytes, Encoding::Hex))
+            .or_else(|| as_base64().map(|bytes| (bytes, Encoding::Base64)))
+            .ok_or(PhyPayloadError::NotHexOrBase64);
+    };
+    let decoded = match encoding {
+        Encoding::Hex => as_hex(),
+        Encoding::Base64 => as_base64(),
+    };
+    decoded
+        .map(|bytes| (bytes, encoding))
+        .ok_or(PhyPayloadError::NotEncoded(encoding))
+}
+
+fn u64_le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |acc, b| (acc << 8) | *b as u64)
+}
+
+fn u24_le(bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(0, |acc, b| (acc << 8) | *b as u32)
+}
+
+impl fmt::Display for MType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::JoinRequest => "Join-Request",
+            Self::JoinAccept => "Join-Accept",
+            Self::UnconfirmedDataUp => "Unconfirmed Data Up",
+            Self::UnconfirmedDataDown => "Unconfirmed Data Down",
+            Self::ConfirmedDataUp => "Confirmed Data Up",
+            Self::ConfirmedDataDown => "Confirmed Data Down",
+            Self::RejoinRequest => "Rejoin-Request",
+            Self::Proprietary => "Proprietary",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hex => f.write_str("hex"),
+            Self::Base64 => f.write_str("base64"),
+        }
+    }
+}
+
+impl fmt::Display for PhyPayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty PHYPayload"),
+            Self::NotHexOrBase64 => write!(f, "not hex or base64"),
+            Self::NotEncoded(encoding) => write!(f, "not valid {encoding}"),
+            Self::TooShort { mtype, len, min } => {
+                write!(
+                    f,
+                    "{len} bytes is too short for a {mtype}, need at least {min}"
+                )
+            }
+            Self::WrongLength {
+                mtype,
+                len,
+                expected,
+            } => {
+                let expected: Vec<_> = expected.iter().map(|e| e.to_string()).collect();
+                write!(f, "a {mtype} is {} bytes, got {len}", expected.join(" or "))
+            }
+            Self::FOptsTooLong {
+                fopts_len,
+                available,
+            } => write!(
+                f,
+                "FCtrl says {fopts_len} bytes of FOpts, but only {available} are left"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PhyPayloadError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_uplink() {
+        // Unconfirmed up, DevAddr 26011BDA, ADR, FCnt 1, one FOpts byte,
+        // FPort 1.
+        let (phy, encoding) =
+            PhyPayload::from_input("40 DA1B0126 81 0100 02 01 C5 AA0BB0CC").unwrap();
+        assert_eq!(encoding, Encoding::Hex);
+        assert_eq!(phy.mhdr.mtype, MType::UnconfirmedDataUp);
+        assert_eq!(phy.mic, Some([0xAA, 0x0B, 0xB0, 0xCC]));
+
+        let MacPayload::Data(data) = &phy.payload else {
+            panic!("not data: {:?}", phy.payload);
+        };
+        assert_eq!(data.fhdr.dev_addr, 0x26011BDA);
+        assert!(data.fhdr.fctrl.adr());
+        assert!(!data.fhdr.fctrl.f_pending());
+        assert_eq!(data.fhdr.fcnt, 1);
+        assert_eq!(data.fhdr.fopts, [0x02]);
+        assert_eq!(data.fport, Some(1));
+        assert_eq!(data.frm_payload, [0xC5]);
+        assert_eq!(data.fhdr.devaddr().unwrap().net_id().as_hex(), "000013");
+        assert!(phy.warnings().is_empty());
+
+        let base64 = base64::engine::general_purpose::STANDARD.encode(&phy.bytes);
+        let (from_base64, encoding) = PhyPayload::from_input(&base64).unwrap();
+        assert_eq!(encoding, Encoding::Base64);
+        assert_eq!(from_base64, phy);
+
+        // FPort 1 and nothing after it.
+        let (phy, _) = PhyPayload::from_input("40 DA1B0126 00 0100 01 AA0BB0CC").unwrap();
+        assert_eq!(
+            phy.warnings(),
+            ["FPort 1 with an empty FRMPayload, FPort should be left out"]
+        );
+    }
+
+    #[test]
+    fn join_request() {
+        let (phy, _) =
+            PhyPayload::from_input("00 0807060504030201 1817161514131211 3412 01020304").unwrap();
+        assert_eq!(
+            phy.payload,
+            MacPayload::JoinRequest(JoinRequest {
+                join_eui: 0x0102030405060708,
+                dev_eui: 0x1112131415161718,
+                dev_nonce: 0x1234,
+            })
+        );
+    }
+
+    #[test]
+    fn bad_frames() {
+        assert_eq!(PhyPayload::from_input(" "), Err(PhyPayloadError::Empty));
+        assert_eq!(
+            PhyPayload::from_input("not a frame!"),
+            Err(PhyPayloadError::NotHexOrBase64)
+        );
+        assert_eq!(
+            PhyPayload::from_input_as("QNobASaBAQACAcWqC7DM", Some(Encoding::Hex)),
+            Err(PhyPayloadError::NotEncoded(Encoding::Hex))
+        );
+        assert!(matches!(
+            PhyPayload::from_input("00 0102"),
+            Err(PhyPayloadError::TooShort { len: 3, .. })
+        ));
+        assert!(matches!(
+            PhyPayload::from_input("00 0102030405 AA0BB0CC"),
+            Err(PhyPayloadError::WrongLength { len: 10, .. })
+        ));
+        assert_eq!(
+            PhyPayload::from_input("40 DA1B0126 8F 0100 AA0BB0CC"),
+            Err(PhyPayloadError::FOptsTooLong {
+                fopts_len: 15,
+                available: 0
+            })
+        );
+    }
+
+    #[test]
+    fn ambiguous_encoding() {
+        assert_eq!(decode_input("ABCD"), Ok((vec![0xAB, 0xCD], Encoding::Hex)));
+        assert_eq!(
+            decode_input_as("ABCD", Some(Encoding::Base64)),
+            Ok((vec![0x00, 0x10, 0x83], Encoding::Base64))
+        );
+    }
+
+    #[test]
+    fn join_accept_fields() {
+        let ja = JoinAccept::from_bytes(&[
+            0x01, 0x02, 0x03, 0x13, 0x00, 0x00, 0xDA, 0x1B, 0x01, 0x26, 0x83, 0x01,
+        ])
+        .unwrap();
+        assert_eq!(ja.join_nonce, 0x030201);
+        assert_eq!(ja.net_id, 0x000013);
+        assert_eq!(ja.dev_addr, 0x26011BDA);
+        assert!(ja.opt_neg());
+        assert_eq!(ja.rx2_data_rate(), 3);
+        assert_eq!(ja.cf_list, None);
+    }
+}
@@ -2,8 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 // When compiling natively:
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "gui"))]
 fn main() -> eframe::Result<()> {
+    // Any arguments means we're being used from the command line.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(bit_looker::cli::run(&args));
+    }
+
     // Log to stdout (if you run with `RUST_LOG=debug`).
     tracing_subscriber::fmt::init();
 
@@ -15,8 +21,15 @@ fn main() -> eframe::Result<()> {
     )
 }
 
+// Without the GUI only the command line is available.
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "gui")))]
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(bit_looker::cli::run(&args));
+}
+
 // when compiling to web using trunk.
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "gui"))]
 fn main() {
     wasm_bindgen_futures::spawn_local(async {
         let runner = eframe::WebRunner::new();
@@ -1,8 +1,11 @@
-use crate::{app::MyStyles, num_format::ToFormattedString};
+use crate::{
+    addressing::{Devaddr, NetID, NetIdError},
+    app::MyStyles,
+    batch, compose,
+    num_format::ToFormattedString,
+    operators, planner, subnet,
+};
 use egui::Color32;
-use std::str::FromStr;
-
-type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
@@ -14,6 +17,18 @@ pub struct State {
     common: Vec<CommonNetId>,
     new_name: String,
     new_net_id: String,
+    /// Index of the entry being edited, with its name and NetID so far.
+    #[serde(skip)]
+    editing: Option<(usize, String, String)>,
+    #[serde(skip)]
+    import: String,
+    #[serde(skip)]
+    import_errors: Vec<String>,
+    operator_search: String,
+    batch: batch::State,
+    compose: compose::State,
+    planner: planner::State,
+    subnet: subnet::State,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -23,9 +38,10 @@ pub struct Styles {
     devaddr_addr: Color32,
     net_id_mem_type: Color32,
     net_id_id: Color32,
+    net_id_unused: Color32,
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct CommonNetId {
     name: String,
     net_id: String,
@@ -38,6 +54,61 @@ impl CommonNetId {
             net_id: net_id.to_string(),
         }
     }
+
+    /// Validate `net_id` and store it as 6 hex digits so every entry looks
+    /// the same no matter how it was typed.
+    fn parse(name: &str, net_id: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("name is empty".to_string());
+        }
+        let n = NetID::new(net_id).map_err(|e| format!("{name}: {e}"))?;
+        Ok(Self::new(name, &n.as_hex()))
+    }
+}
+
+fn export_json(common: &[CommonNetId]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(common)
+}
+
+fn export_csv(common: &[CommonNetId]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for entry in common {
+        writer.serialize(entry)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Read a list exported as JSON, or CSV with a `name,net_id` header.
+///
+/// Entries that don't validate are left out and reported.
+fn import(input: &str) -> (Vec<CommonNetId>, Vec<String>) {
+    let input = input.trim();
+    let raw: Vec<Result<CommonNetId, String>> = if input.starts_with('[') {
+        match serde_json::from_str::<Vec<CommonNetId>>(input) {
+            Ok(entries) => entries.into_iter().map(Ok).collect(),
+            Err(e) => return (vec![], vec![format!("JSON: {e}")]),
+        }
+    } else {
+        csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(input.as_bytes())
+            .deserialize()
+            .enumerate()
+            // Line 1 is the header.
+            .map(|(idx, row)| row.map_err(|e| format!("line {}: {e}", idx + 2)))
+            .collect()
+    };
+
+    let mut entries = vec![];
+    let mut errors = vec![];
+    for entry in raw {
+        match entry.and_then(|e: CommonNetId| CommonNetId::parse(&e.name, &e.net_id)) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(e),
+        }
+    }
+    (entries, errors)
 }
 
 impl Default for Styles {
@@ -48,6 +119,7 @@ impl Default for Styles {
             devaddr_addr: Color32::RED,
             net_id_mem_type: Color32::BLUE,
             net_id_id: Color32::GREEN,
+            net_id_unused: Color32::GRAY,
         }
     }
 }
@@ -57,6 +129,7 @@ impl Styles {
         Self {
             net_id_mem_type: self.net_id_mem_type,
             net_id_id: self.net_id_id,
+            net_id_unused: self.net_id_unused,
             ..Default::default()
         }
     }
@@ -80,10 +153,18 @@ impl Default for State {
                 CommonNetId::new("Helium", "00003C"),
                 CommonNetId::new("Helium", "000024"),
                 CommonNetId::new("Helium", "C00053"),
-                CommonNetId::new("Helium", "600053"),
+                CommonNetId::new("Helium", "60002D"),
             ],
             new_name: Default::default(),
             new_net_id: Default::default(),
+            editing: None,
+            import: Default::default(),
+            import_errors: Default::default(),
+            operator_search: Default::default(),
+            batch: Default::default(),
+            compose: Default::default(),
+            planner: Default::default(),
+            subnet: Default::default(),
         }
     }
 }
@@ -91,12 +172,23 @@ impl Default for State {
 impl State {
     pub fn side_panel(&mut self, ui: &mut egui::Ui) {
         ui.heading("Common Net ID");
+        self.common_net_ids(ui);
+        ui.separator();
 
-        for common in self.common.iter() {
-            ui.horizontal(|ui| {
-                ui.label(&common.name);
-                if ui.button(&common.net_id).clicked() {
-                    self.net_id.clone_from(&common.net_id);
+        ui.heading("Operator Directory");
+        ui.weak(format!(
+            "A sample of {} public NetIDs, not the full LoRa Alliance list",
+            operators::directory().len()
+        ));
+        ui.add(egui::TextEdit::singleline(&mut self.operator_search).hint_text("Search"));
+        if !self.operator_search.trim().is_empty() {
+            egui::Grid::new("operator_search").show(ui, |ui| {
+                for op in operators::search(&self.operator_search) {
+                    ui.label(&op.name);
+                    if ui.button(op.net_id.as_hex()).clicked() {
+                        self.net_id = op.net_id.as_hex();
+                    }
+                    ui.end_row();
                 }
             });
         }
@@ -120,6 +212,14 @@ impl State {
             );
             ui.end_row();
 
+            ui.label("Not in Devaddr:");
+            egui::color_picker::color_edit_button_srgba(
+                ui,
+                &mut self.styles.net_id_unused,
+                egui::color_picker::Alpha::Opaque,
+            );
+            ui.end_row();
+
             ui.end_row();
             if ui.button("Reset").clicked() {
                 self.styles = self.styles.reset_net_id();
@@ -157,11 +257,150 @@ impl State {
         });
     }
 
+    fn common_net_ids(&mut self, ui: &mut egui::Ui) {
+        let Self {
+            net_id,
+            common,
+            new_name,
+            new_net_id,
+            editing,
+            import: import_text,
+            import_errors,
+            ..
+        } = self;
+
+        let mut remove = None;
+        let mut edit_error = None;
+        egui::Grid::new("common_net_ids").show(ui, |ui| {
+            for (idx, entry) in common.iter_mut().enumerate() {
+                match editing {
+                    Some((editing_idx, name, edit_net_id)) if *editing_idx == idx => {
+                        ui.add(egui::TextEdit::singleline(name).desired_width(80.0));
+                        ui.add(egui::TextEdit::singleline(edit_net_id).desired_width(60.0));
+                        let parsed = CommonNetId::parse(name, edit_net_id);
+                        if ui
+                            .add_enabled(parsed.is_ok(), egui::Button::new("Save"))
+                            .clicked()
+                        {
+                            *entry = parsed.clone().expect("enabled when valid");
+                            *editing = None;
+                        } else if ui.button("Cancel").clicked() {
+                            *editing = None;
+                        }
+                        edit_error = parsed.err();
+                    }
+                    _ => {
+                        ui.label(&entry.name);
+                        if ui.button(&entry.net_id).clicked() {
+                            net_id.clone_from(&entry.net_id);
+                        }
+                        if ui.small_button("✏").on_hover_text("Edit").clicked() {
+                            *editing = Some((idx, entry.name.clone(), entry.net_id.clone()));
+                        }
+                        if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                            remove = Some(idx);
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(e) = edit_error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+        if let Some(idx) = remove {
+            common.remove(idx);
+            *editing = None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(new_name)
+                    .hint_text("Name")
+                    .desired_width(80.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(new_net_id)
+                    .hint_text("Net ID")
+                    .desired_width(60.0),
+            );
+            let parsed = CommonNetId::parse(new_name, new_net_id);
+            if ui
+                .add_enabled(parsed.is_ok(), egui::Button::new("Add"))
+                .clicked()
+            {
+                common.extend(parsed.ok());
+                new_name.clear();
+                new_net_id.clear();
+            }
+        });
+        if !new_name.is_empty() || !new_net_id.is_empty() {
+            if let Err(e) = CommonNetId::parse(new_name, new_net_id) {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+
+        ui.collapsing("Import / Export", |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Copy JSON").clicked() {
+                    match export_json(common) {
+                        Ok(json) => ui.output_mut(|o| o.copied_text = json),
+                        Err(e) => import_errors.push(e.to_string()),
+                    }
+                }
+                if ui.button("Copy CSV").clicked() {
+                    match export_csv(common) {
+                        Ok(csv) => ui.output_mut(|o| o.copied_text = csv),
+                        Err(e) => import_errors.push(e.to_string()),
+                    }
+                }
+            });
+            ui.add(
+                egui::TextEdit::multiline(import_text)
+                    .hint_text("name,net_id\nHelium,00003C")
+                    .desired_rows(3),
+            );
+            ui.horizontal(|ui| {
+                let add = ui.button("Add to list").clicked();
+                let replace = ui.button("Replace list").clicked();
+                if add || replace {
+                    let (entries, errors) = import(import_text);
+                    if replace {
+                        common.clear();
+                        *editing = None;
+                    }
+                    for entry in entries {
+                        if !common.contains(&entry) {
+                            common.push(entry);
+                        }
+                    }
+                    if errors.is_empty() {
+                        import_text.clear();
+                    }
+                    *import_errors = errors;
+                }
+            });
+            for e in import_errors.iter() {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        });
+    }
+
+    /// Show `devaddr` in the Devaddr field, for other views linking here.
+    pub fn inspect_devaddr(&mut self, devaddr: String) {
+        self.devaddr = devaddr;
+    }
+
     pub fn main_view(&mut self, ui: &mut egui::Ui, _styles: &mut MyStyles) {
         let Self {
             net_id,
             devaddr,
             styles,
+            common,
+            batch,
+            compose,
+            planner,
+            subnet,
             ..
         } = self;
 
@@ -172,7 +411,11 @@ impl State {
             ui.add(net_id_field);
         });
 
-        if let Ok(n) = NetID::new(net_id) {
+        let parsed = NetID::new(net_id);
+        if let Err(e) = &parsed {
+            show_error(ui, net_id, e);
+        }
+        if let Ok(n) = parsed {
             // Outside of grid to not ruin the spacing
             ui.horizontal(|ui| {
                 ui.label("Bin:");
@@ -180,36 +423,34 @@ impl State {
             });
             egui::Grid::new("net_id_grid").show(ui, |ui| {
                 ui.label("Type:");
-                ui.label(n.mem_type.to_string());
-
-                if ui
-                    .add_enabled(n.mem_type > 0, egui::Button::new("-"))
-                    .clicked()
-                {
-                    let nn = NetID::with_fields(n.mem_type - 1, n.id);
-                    *net_id = nn.as_hex();
-                }
-                if ui
-                    .add_enabled(n.mem_type < 7, egui::Button::new("+"))
-                    .clicked()
-                {
-                    let nn = NetID::with_fields(n.mem_type + 1, n.id);
-                    *net_id = nn.as_hex();
-                }
+                ui.label(n.mem_type().to_string());
+
+                step_button(ui, "-", n.mem_type() > 0, net_id, || {
+                    NetID::with_fields(n.mem_type() - 1, n.id())
+                });
+                step_button(ui, "+", n.mem_type() < 7, net_id, || {
+                    NetID::with_fields(n.mem_type() + 1, n.id())
+                });
                 ui.end_row();
 
                 // ======================
                 ui.label("NwkID:");
-                ui.label(n.id.to_string());
-
-                if ui.add_enabled(n.id > 0, egui::Button::new("-")).clicked() {
-                    let nn = NetID::with_fields(n.mem_type, n.id - 1);
-                    *net_id = nn.as_hex();
-                }
-                if ui.add_enabled(true, egui::Button::new("+")).clicked() {
-                    let nn = NetID::with_fields(n.mem_type, n.id + 1);
-                    *net_id = nn.as_hex();
-                }
+                ui.label(n.id().to_string());
+                step_button(ui, "-", n.id() > 0, net_id, || {
+                    NetID::with_fields(n.mem_type(), n.id() - 1)
+                });
+                step_button(ui, "+", n.id() < n.max_id(), net_id, || {
+                    NetID::with_fields(n.mem_type(), n.id() + 1)
+                });
+                ui.end_row();
+                // ======================
+                ui.label("In Devaddr:");
+                ui.label(n.nwk_id().to_string());
+                ui.label(format!("{} of {} bits", n.nwk_id_bits(), n.id_bits()));
+                ui.end_row();
+                // ======================
+                ui.label("Operator:");
+                show_operator(ui, &n);
                 ui.end_row();
                 // ======================
                 ui.label("Hex:");
@@ -226,19 +467,14 @@ impl State {
                 // ======================
                 let start = n.start_addr();
                 let end = n.end_addr();
-                let size = end.addr - start.addr + 1;
 
                 ui.label("Size:");
-                ui.label(size.to_formatted_string());
-                ui.label(format!("{} bits", addr_offset_for_mem_type(n.mem_type)));
+                ui.label(n.size().to_formatted_string());
+                ui.label(format!("{} bits", n.addr_bits()));
                 ui.end_row();
                 // ======================
                 ui.label("Subnet:");
-                ui.label(format!(
-                    "{}/{}",
-                    start.as_hex(),
-                    32 - addr_offset_for_mem_type(n.mem_type)
-                ));
+                ui.label(format!("{}/{}", start.as_hex(), 32 - n.addr_bits()));
                 ui.end_row();
                 // ======================
                 ui.label("Range");
@@ -261,7 +497,11 @@ impl State {
             ui.add(devaddr_field);
         });
 
-        if let Ok(d) = Devaddr::new(devaddr) {
+        let parsed = Devaddr::new(devaddr);
+        if let Err(e) = &parsed {
+            show_error(ui, devaddr, e);
+        }
+        if let Ok(d) = parsed {
             ui.horizontal(|ui| {
                 ui.label("Bin:");
                 d.as_bin(ui, styles);
@@ -285,202 +525,91 @@ impl State {
                 if ui.button(d.net_id().as_hex()).clicked() {
                     *net_id = d.net_id().as_hex();
                 }
+                show_operator(ui, &d.net_id());
                 ui.end_row();
                 // ======================
-                let net_id = d.net_id();
-                let start = net_id.start_addr();
-                let end = net_id.end_addr();
-                let size = end.addr - start.addr + 1;
-
-                let per = ((d.addr + 1) as f32 / size as f32) * 100.0;
+                let size = d.net_id().size();
+                let per = ((d.addr() + 1) as f32 / size as f32) * 100.0;
 
                 ui.label("Addr:");
                 ui.label(format!(
                     "{} of {}",
-                    (d.addr + 1).to_formatted_string(),
+                    (d.addr() + 1).to_formatted_string(),
                     size.to_formatted_string()
                 ));
                 ui.label(format!("{}%", per));
                 ui.end_row();
             });
         }
-    }
-}
 
-#[derive(Debug)]
-struct Devaddr {
-    mem_type: u8,
-    nwk_addr: u32,
-    addr: u32,
-}
+        // ====================================================================
+        ui.separator();
 
-struct NetID {
-    mem_type: u8,
-    id: u32,
-    dec: u32,
-}
+        let common: Vec<_> = common
+            .iter()
+            .filter_map(|c| Some((c.name.as_str(), NetID::new(&c.net_id).ok()?)))
+            .collect();
 
-impl Devaddr {
-    fn new(input: &str) -> Result<Self> {
-        let devaddr = match u32::from_str_radix(input, 16) {
-            Err(_) => u32::from_str(input),
-            Ok(devaddr) => Ok(devaddr),
-        }?;
-
-        let (mem_type, nwk_addr, addr) = {
-            let mem_type = devaddr.leading_ones() as u8;
-
-            let nwk_addr_start = mem_type as usize + 1;
-            let nwk_addr_end = nwk_addr_start + nwk_id_offset_for_mem_type(mem_type);
-
-            let addr_start = 32 - addr_offset_for_mem_type(mem_type);
-            let addr_end = 32;
-            (
-                mem_type,
-                new_num_from(devaddr, nwk_addr_start..=nwk_addr_end),
-                new_num_from(devaddr, addr_start..=addr_end),
-            )
-        };
-        Ok(Self {
-            mem_type,
-            nwk_addr,
-            addr,
-        })
-    }
+        ui.collapsing("Compose Devaddr", |ui| {
+            compose.main_view(ui, net_id, common.iter().copied(), devaddr)
+        });
 
-    fn with_fields(mem_type: u8, id: u32, addr: u32) -> Self {
-        let addr_bits_to_shift = addr_offset_for_mem_type(mem_type);
-        Self {
-            mem_type,
-            nwk_addr: id,
-            addr: new_num_from(addr, (32 - addr_bits_to_shift)..=32),
-        }
-    }
+        // Open the section to show what was dropped on the window.
+        let dropped = batch.read_dropped_files(ui.ctx());
+        egui::CollapsingHeader::new("Batch Classify")
+            .open(dropped.then_some(true))
+            .show(ui, |ui| batch.main_view(ui, common.iter().copied()));
 
-    fn num(&self) -> u32 {
-        let mem_type_bits = match self.mem_type {
-            0 => 0,
-            1 => 0b10 << 30,
-            2 => 0b110 << 29,
-            3 => 0b1110 << 28,
-            4 => 0b11110 << 27,
-            5 => 0b111110 << 26,
-            6 => 0b1111110 << 25,
-            7 => 0b11111110 << 24,
-            _ => panic!("invalid mem_type: {}", self.mem_type),
-        };
-
-        let addr_bits_to_shift = addr_offset_for_mem_type(self.mem_type);
-
-        let nwk_id_bits = self.nwk_addr << addr_bits_to_shift;
-        let addr_bits = new_num_from(self.addr, (32 - addr_bits_to_shift)..=32);
-        mem_type_bits | nwk_id_bits | addr_bits
-    }
+        ui.collapsing("Allocation Planner", |ui| planner.main_view(ui));
 
-    fn net_id(&self) -> NetID {
-        NetID::with_fields(self.mem_type, self.nwk_addr)
+        ui.collapsing("Subnet Calculator", |ui| subnet.main_view(ui, net_id));
     }
 }
 
-impl NetID {
-    fn new(input: &str) -> Result<Self> {
-        match u32::from_str_radix(input, 16) {
-            Ok(net_id) => {
-                let mem_type = new_num_from(net_id, 8..=11);
-                let nwk_addr = new_num_from(net_id, 12..=32);
-                Ok(Self {
-                    mem_type: mem_type as u8,
-                    id: nwk_addr,
-                    dec: net_id,
-                })
-            }
-            Err(e) => Err(Box::new(e)),
-        }
-    }
-
-    fn with_fields(mem_type: u8, id: u32) -> Self {
-        let leading = (mem_type as u32) << 21;
-        let val = leading | id;
-        Self {
-            mem_type,
-            id,
-            dec: val,
-        }
-    }
-
-    fn num(&self) -> u32 {
-        let leading = (self.mem_type as u32) << 21;
-        leading | self.id
-    }
-
-    fn start_addr(&self) -> Devaddr {
-        Devaddr::with_fields(self.mem_type, self.id, u32::MIN)
-    }
-
-    fn end_addr(&self) -> Devaddr {
-        Devaddr::with_fields(self.mem_type, self.id, u32::MAX)
+/// Explain why `input` didn't parse, unless there's nothing to explain yet.
+fn show_error(ui: &mut egui::Ui, input: &str, error: &impl std::fmt::Display) {
+    if !input.trim().is_empty() {
+        ui.colored_label(ui.visuals().error_fg_color, error.to_string());
     }
 }
 
-fn new_num_from(num: u32, range: std::ops::RangeInclusive<usize>) -> u32 {
-    let end_bit = 32 - range.start() - 1;
-    let start_bit = 32 - range.end(); // inclusive, don't subtract 1
-
-    // Create a mask with ones in the specified range of bits.
-    let mask = ((1 << (end_bit - start_bit + 1)) - 1) << start_bit;
-
-    // Use the mask to extract the desired bits from num.
-    (num & mask) >> start_bit
+fn show_operator(ui: &mut egui::Ui, net_id: &NetID) {
+    match operators::lookup(net_id) {
+        Some(op) => ui.label(&op.name),
+        None => ui.weak("Operator not in the bundled table"),
+    };
 }
 
-fn nwk_id_offset_for_mem_type(mem_type: u8) -> usize {
-    match mem_type {
-        0 => 6,
-        1 => 6,
-        2 => 9,
-        3 => 11,
-        4 => 12,
-        5 => 13,
-        6 => 15,
-        7 => 17,
-        _ => panic!("Invalid DevAddr: must have at least one leading zero"),
+/// A button that moves to the NetID `next` builds, disabled when that isn't
+/// `possible` or isn't a valid NetID.
+fn step_button(
+    ui: &mut egui::Ui,
+    text: &str,
+    possible: bool,
+    net_id: &mut String,
+    next: impl FnOnce() -> Result<NetID, NetIdError>,
+) {
+    let next = possible.then(next);
+    let mut response = ui.add_enabled(matches!(next, Some(Ok(_))), egui::Button::new(text));
+    if let Some(Err(e)) = &next {
+        response = response.on_disabled_hover_text(e.to_string());
     }
-}
-
-fn addr_offset_for_mem_type(mem_type: u8) -> usize {
-    match mem_type {
-        0 => 25,
-        1 => 24,
-        2 => 20,
-        3 => 17,
-        4 => 15,
-        5 => 13,
-        6 => 10,
-        7 => 7,
-        _ => panic!("invalid mem_type: {}", mem_type),
+    if response.clicked() {
+        if let Some(Ok(nn)) = next {
+            *net_id = nn.as_hex();
+        }
     }
 }
 
 trait Printable {
-    fn as_hex(&self) -> String;
-    fn as_dec(&self) -> String;
     fn as_bin(&self, ui: &mut egui::Ui, styles: &Styles);
-    fn as_arr(&self) -> String;
 }
 
 impl Printable for Devaddr {
-    fn as_hex(&self) -> String {
-        format!("{:08X}", self.num())
-    }
-
-    fn as_dec(&self) -> String {
-        self.num().to_string()
-    }
-
     fn as_bin(&self, ui: &mut egui::Ui, styles: &Styles) {
         ui.horizontal(|ui| {
-            let type_bit = (self.mem_type + 1) as usize;
-            let nwk_bit = nwk_id_offset_for_mem_type(self.mem_type) + type_bit;
+            let type_bit = (self.mem_type() + 1) as usize;
+            let nwk_bit = self.nwk_id_bits() + type_bit;
 
             let binary = format!("{:032b}", self.num());
             for (idx, ch) in binary.chars().enumerate() {
@@ -499,30 +628,18 @@ impl Printable for Devaddr {
             }
         });
     }
-
-    fn as_arr(&self) -> String {
-        format!("{:?}", self.num().to_be_bytes())
-    }
 }
 
 impl Printable for NetID {
-    fn as_hex(&self) -> String {
-        format!("{:06X}", self.num())
-    }
-
-    fn as_dec(&self) -> String {
-        self.dec.to_string()
-    }
-
     fn as_bin(&self, ui: &mut egui::Ui, styles: &Styles) {
         ui.horizontal(|ui| {
             use std::fmt::Write;
 
             let mut output = String::new();
-            let rfu_bits = match self.mem_type {
-                0..=2 => 15 + 3, // include type bits
-                _ => 0,
-            };
+            // RFU for types 0-2, then the part of the ID that
+            // doesn't fit in a Devaddr for types 3-7.
+            let rfu_bits = 24 - self.id_bits();
+            let unused_bits = 24 - self.nwk_id_bits();
 
             let binary = format!("{:024b}", self.num());
             for (idx, ch) in binary.chars().enumerate() {
@@ -530,6 +647,7 @@ impl Printable for NetID {
                 let out = match idx {
                     x if x < 3 => ch.color(styles.net_id_mem_type),
                     x if x < rfu_bits => ch.color(Color32::DARK_GRAY).strikethrough(),
+                    x if x < unused_bits => ch.color(styles.net_id_unused),
                     _ => ch.color(styles.net_id_id),
                 };
 
@@ -546,26 +664,35 @@ impl Printable for NetID {
 
         // print_binary_bytes(self.dec, 3)
     }
-
-    fn as_arr(&self) -> String {
-        let num: u32 = self.num();
-        let arr: [u8; 4] = num.to_be_bytes();
-        format!("[{}, {}, {}]", arr[1], arr[2], arr[3])
-    }
 }
 
-impl FromStr for Devaddr {
-    type Err = String;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Devaddr::new(s).map_err(|_| "Invalid Devaddr".to_owned())
+    #[test]
+    fn common_round_trip() {
+        let common = State::default().common;
+
+        let (entries, errors) = import(&export_json(&common).unwrap());
+        assert!(errors.is_empty());
+        assert_eq!(entries, common);
+
+        let csv = export_csv(&common).unwrap();
+        assert_eq!(csv.lines().next(), Some("name,net_id"));
+        let (entries, errors) = import(&csv);
+        assert!(errors.is_empty());
+        assert_eq!(entries, common);
     }
-}
 
-impl FromStr for NetID {
-    type Err = String;
+    #[test]
+    fn import_validates() {
+        let (entries, errors) = import("name,net_id\nAcme, 3c\n,000024\nBad,zz\n");
+        assert_eq!(entries, [CommonNetId::new("Acme", "00003C")]);
+        assert_eq!(errors.len(), 2);
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        NetID::new(s).map_err(|_| "Invalid Net ID".to_owned())
+        let (entries, errors) = import("[{\"name\": \"Acme\"}]");
+        assert!(entries.is_empty());
+        assert_eq!(errors.len(), 1);
     }
 }
@@ -0,0 +1,118 @@
+//! Offline sample of publicly assigned NetIDs, bundled from
+//! `assets/net_id_operators.csv`. A NetID missing from it may still be
+//! assigned.
+
+use crate::addressing::{Devaddr, NetID};
+use std::sync::OnceLock;
+
+const DIRECTORY_CSV: &str = include_str!("../assets/net_id_operators.csv");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operator {
+    pub net_id: NetID,
+    pub name: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Record {
+    net_id: String,
+    operator: String,
+}
+
+/// Every entry in the bundled table, parsed once.
+pub fn directory() -> &'static [Operator] {
+    static DIRECTORY: OnceLock<Vec<Operator>> = OnceLock::new();
+    DIRECTORY.get_or_init(|| {
+        let (operators, errors) = parse(DIRECTORY_CSV);
+        debug_assert!(errors.is_empty(), "bad operator rows: {errors:?}");
+        operators
+    })
+}
+
+/// The operator `net_id` is assigned to, if it's in the table.
+pub fn lookup(net_id: &NetID) -> Option<&'static Operator> {
+    directory().iter().find(|op| op.net_id == *net_id)
+}
+
+/// The operator of the NetID `devaddr` belongs to. Types 3-7 Devaddrs only
+/// carry the low NwkID bits of the NetID, so those match on type and NwkID.
+pub fn lookup_devaddr(devaddr: &Devaddr) -> Option<&'static Operator> {
+    directory().iter().find(|op| op.owns(devaddr))
+}
+
+impl Operator {
+    fn owns(&self, devaddr: &Devaddr) -> bool {
+        self.net_id.mem_type() == devaddr.mem_type() && self.net_id.nwk_id() == devaddr.nwk_addr()
+    }
+}
+
+/// Entries whose operator name or NetID contains `query`, ignoring case.
+pub fn search(query: &str) -> impl Iterator<Item = &'static Operator> {
+    let query = query.trim().to_lowercase();
+    directory().iter().filter(move |op| {
+        op.name.to_lowercase().contains(&query)
+            || op.net_id.as_hex().to_lowercase().contains(&query)
+    })
+}
+
+fn parse(input: &str) -> (Vec<Operator>, Vec<String>) {
+    let mut operators = vec![];
+    let mut errors = vec![];
+    let mut reader = csv::ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+    for record in reader.deserialize::<Record>() {
+        match record {
+            Ok(record) => match NetID::new(&record.net_id) {
+                Ok(net_id) => operators.push(Operator {
+                    net_id,
+                    name: record.operator,
+                }),
+                Err(e) => errors.push(format!("{}: {e}", record.net_id)),
+            },
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    (operators, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_parses() {
+        let (operators, errors) = parse(DIRECTORY_CSV);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(operators.len(), directory().len());
+    }
+
+    #[test]
+    fn lookups() {
+        let ttn = NetID::new("000013").unwrap();
+        assert_eq!(lookup(&ttn).unwrap().name, "The Things Network");
+
+        let d = Devaddr::new("78000001").unwrap();
+        assert_eq!(lookup_devaddr(&d).unwrap().name, "Helium");
+
+        assert!(lookup(&NetID::new("E00000").unwrap()).is_none());
+        assert_eq!(search("helium").count(), 4);
+        assert_eq!(search("0013").count(), 1);
+        assert_eq!(search("orange").count(), 1);
+    }
+
+    #[test]
+    fn type_6_prefix_match() {
+        let helium = NetID::new("C00053").unwrap();
+        let d = helium.devaddr_at(1).unwrap();
+        assert_eq!(d.mem_type(), 6);
+        assert_eq!(lookup_devaddr(&d).unwrap().name, "Helium");
+
+        // Only the low 15 bits of a type 6 ID end up in the Devaddr.
+        let (operators, _) = parse("net_id,operator\nC10053,Upper ID bits set\n");
+        assert_ne!(operators[0].net_id, helium);
+        assert!(operators[0].owns(&d));
+        assert!(!operators[0].owns(&NetID::new("C00054").unwrap().devaddr_at(1).unwrap()));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    eui,
+    gwmp::{Datagram, Identifier},
+    phy_payload::show_phy_payload,
+};
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+}
+
+impl State {
+    /// Returns a Devaddr to look at in the NetID view when one is clicked.
+    pub fn main_view(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        ui.label("UDP payload:");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.input)
+                .hint_text("hex, base64, or a Wireshark, xxd or hexdump -C dump")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY)
+                .desired_rows(4),
+        );
+
+        if self.input.trim().is_empty() {
+            return None;
+        }
+        let datagram = match Datagram::from_input(&self.input) {
+            Ok(datagram) => datagram,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+
+        egui::Grid::new("gwmp_header").striped(true).show(ui, |ui| {
+            ui.label("Version:");
+            match datagram.version {
+                1 | 2 => ui.label(datagram.version.to_string()),
+                v => ui.colored_label(ui.visuals().warn_fg_color, format!("{v} (unknown)")),
+            };
+            ui.end_row();
+
+            ui.label("Token:");
+            ui.monospace(format!("{:04X}", datagram.token));
+            ui.end_row();
+
+            ui.label("Identifier:");
+            ui.label(format!(
+                "{} ({})",
+                datagram.identifier,
+                if datagram.identifier.is_upstream() {
+                    "gateway to server"
+                } else {
+                    "server to gateway"
+                }
+            ));
+            ui.end_row();
+
+            if let Some(gateway) = datagram.gateway {
+                ui.label("Gateway EUI:");
+                let vendor = eui::vendor(&gateway)
+                    .map(|a| format!(" ({})", a.name))
+                    .unwrap_or_default();
+                ui.monospace(format!("{}{vendor}", gateway.as_dashed()));
+                ui.end_row();
+            }
+        });
+
+        match &datagram.json {
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None if datagram.identifier == Identifier::PushData
+                || datagram.identifier == Identifier::PullResp =>
+            {
+                ui.colored_label(ui.visuals().warn_fg_color, "no JSON body");
+            }
+            _ => (),
+        }
+
+        let mut inspect = None;
+        for (name, value) in datagram.other_fields() {
+            ui.separator();
+            ui.strong(&name);
+            show_json_fields(ui, &name, &value);
+        }
+        for (idx, packet) in datagram.packets().iter().enumerate() {
+            ui.separator();
+            ui.push_id(idx, |ui| {
+                ui.strong(packet.kind);
+                show_json_fields(
+                    ui,
+                    packet.kind,
+                    &serde_json::Value::Object(packet.fields.clone()),
+                );
+                match &packet.phy {
+                    Some(Ok(phy)) => {
+                        ui.collapsing("PHYPayload", |ui| {
+                            for warning in phy.warnings() {
+                                ui.colored_label(ui.visuals().warn_fg_color, warning);
+                            }
+                            if let Some(devaddr) = show_phy_payload(ui, phy) {
+                                inspect = Some(devaddr);
+                            }
+                        });
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("data: {e}"));
+                    }
+                    None => (),
+                }
+            });
+        }
+        inspect
+    }
+}
+
+/// A grid of an object's fields, or the value itself for anything else.
+pub fn show_json_fields(ui: &mut egui::Ui, id: &str, value: &serde_json::Value) {
+    let serde_json::Value::Object(fields) = value else {
+        ui.monospace(value.to_string());
+        return;
+    };
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for (key, value) in fields {
+            ui.label(format!("{key}:"));
+            match value {
+                serde_json::Value::String(s) => ui.monospace(s),
+                other => ui.monospace(other.to_string()),
+            };
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,373 @@
+use crate::{
+    crypto::{self, MicParams, SessionKeys},
+    lorawan::{self, Encoding, MacPayload, PhyPayload},
+    operators,
+};
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    input: String,
+    /// `None` to guess, hex first.
+    encoding: Option<Encoding>,
+    version: Version,
+    /// Keys are never saved.
+    #[serde(skip)]
+    keys: KeyInputs,
+    #[serde(skip)]
+    mic_params: MicParams,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Version {
+    #[default]
+    V1_0,
+    V1_1,
+}
+
+#[derive(Default)]
+struct KeyInputs {
+    nwk_s_key: String,
+    app_s_key: String,
+    f_nwk_s_int_key: String,
+    s_nwk_s_int_key: String,
+    nwk_s_enc_key: String,
+}
+
+impl KeyInputs {
+    fn session_keys(&self, version: Version) -> Result<SessionKeys, crypto::CryptoError> {
+        let app_s_key = match self.app_s_key.trim() {
+            "" => None,
+            key => Some(crypto::parse_key(key)?),
+        };
+        Ok(match version {
+            Version::V1_0 => SessionKeys::V1_0 {
+                nwk_s_key: crypto::parse_key(&self.nwk_s_key)?,
+                app_s_key,
+            },
+            Version::V1_1 => SessionKeys::V1_1 {
+                f_nwk_s_int_key: crypto::parse_key(&self.f_nwk_s_int_key)?,
+                s_nwk_s_int_key: crypto::parse_key(&self.s_nwk_s_int_key)?,
+                nwk_s_enc_key: crypto::parse_key(&self.nwk_s_enc_key)?,
+                app_s_key,
+            },
+        })
+    }
+}
+
+impl State {
+    /// Fill in the key fields, for checking frames after a join.
+    pub fn use_session_keys(&mut self, session_keys: &SessionKeys) {
+        let hex = |key: &crypto::Key| hex::encode_upper(key);
+        let app_s_key = match session_keys {
+            SessionKeys::V1_0 {
+                nwk_s_key,
+                app_s_key,
+            } => {
+                self.version = Version::V1_0;
+                self.keys.nwk_s_key = hex(nwk_s_key);
+                app_s_key
+            }
+            SessionKeys::V1_1 {
+                f_nwk_s_int_key,
+                s_nwk_s_int_key,
+                nwk_s_enc_key,
+                app_s_key,
+            } => {
+                self.version = Version::V1_1;
+                self.keys.f_nwk_s_int_key = hex(f_nwk_s_int_key);
+                self.keys.s_nwk_s_int_key = hex(s_nwk_s_int_key);
+                self.keys.nwk_s_enc_key = hex(nwk_s_enc_key);
+                app_s_key
+            }
+        };
+        self.keys.app_s_key = app_s_key.as_ref().map(hex).unwrap_or_default();
+        self.mic_params = MicParams::default();
+    }
+
+    /// Returns a Devaddr to look at in the NetID view when one is clicked.
+    pub fn main_view(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        ui.horizontal(|ui| {
+            ui.label("PHYPayload:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.input)
+                    .hint_text("hex or base64")
+                    .desired_width(f32::INFINITY),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Read as:");
+            ui.selectable_value(&mut self.encoding, None, "Auto");
+            ui.selectable_value(&mut self.encoding, Some(Encoding::Hex), "Hex");
+            ui.selectable_value(&mut self.encoding, Some(Encoding::Base64), "Base64");
+        });
+
+        if self.input.trim().is_empty() {
+            return None;
+        }
+        let (phy, encoding) = match PhyPayload::from_input_as(&self.input, self.encoding) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                return None;
+            }
+        };
+        ui.weak(format!("{} bytes, read as {encoding}", phy.bytes.len()));
+        if self.encoding.is_none()
+            && encoding == Encoding::Hex
+            && lorawan::decode_input_as(&self.input, Some(Encoding::Base64)).is_ok()
+        {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "This is valid base64 too, pick Base64 above if that's what it is",
+            );
+        }
+        for warning in phy.warnings() {
+            ui.colored_label(ui.visuals().warn_fg_color, warning);
+        }
+
+        let inspect = show_phy_payload(ui, &phy);
+        if let MacPayload::Data(_) = phy.payload {
+            ui.collapsing("Session Keys", |ui| self.keys_view(ui, &phy));
+        }
+        inspect
+    }
+
+    fn keys_view(&mut self, ui: &mut egui::Ui, phy: &PhyPayload) {
+        let Self {
+            version,
+            keys,
+            mic_params,
+            ..
+        } = self;
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(version, Version::V1_0, "LoRaWAN 1.0.x");
+            ui.selectable_value(version, Version::V1_1, "LoRaWAN 1.1");
+        });
+        egui::Grid::new("session_keys").show(ui, |ui| {
+            let key = |ui: &mut egui::Ui, label: &str, value: &mut String| {
+                ui.label(label);
+                ui.add(
+                    egui::TextEdit::singleline(value)
+                        .hint_text("32 hex digits")
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(280.0),
+                );
+                ui.end_row();
+            };
+            match version {
+                Version::V1_0 => key(ui, "NwkSKey:", &mut keys.nwk_s_key),
+                Version::V1_1 => {
+                    key(ui, "FNwkSIntKey:", &mut keys.f_nwk_s_int_key);
+                    key(ui, "SNwkSIntKey:", &mut keys.s_nwk_s_int_key);
+                    key(ui, "NwkSEncKey:", &mut keys.nwk_s_enc_key);
+                }
+            }
+            key(ui, "AppSKey:", &mut keys.app_s_key);
+
+            ui.label("FCnt MSB:");
+            ui.add(egui::DragValue::new(&mut mic_params.fcnt_msb).hexadecimal(4, false, true))
+                .on_hover_text("Upper 16 bits of the frame counter, which aren't sent");
+            ui.end_row();
+
+            if *version == Version::V1_1 {
+                ui.label("ConfFCnt:");
+                ui.add(egui::DragValue::new(&mut mic_params.conf_fcnt))
+                    .on_hover_text("FCnt of the confirmed frame this ACKs");
+                ui.end_row();
+                if phy.mhdr.mtype.is_uplink() {
+                    ui.label("TxDr:");
+                    ui.add(egui::DragValue::new(&mut mic_params.tx_dr).clamp_range(0..=15));
+                    ui.end_row();
+                    ui.label("TxCh:");
+                    ui.add(egui::DragValue::new(&mut mic_params.tx_ch));
+                    ui.end_row();
+                }
+            }
+        });
+
+        let session_keys = match keys.session_keys(*version) {
+            Ok(session_keys) => session_keys,
+            Err(e) => {
+                ui.weak(e.to_string());
+                return;
+            }
+        };
+        let fcnt_msb = mic_params.fcnt_msb;
+        egui::Grid::new("session_results")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("MIC:");
+                match crypto::verify_mic(phy, &session_keys, mic_params) {
+                    Ok(Ok(())) => {
+                        ui.label("✔ Valid");
+                    }
+                    Ok(Err(failure)) => {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!(
+                                "✖ expected {}: {}",
+                                hex::encode_upper(failure.computed),
+                                failure.reason
+                            ),
+                        );
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                    }
+                }
+                ui.end_row();
+
+                if *version == Version::V1_1 {
+                    if let Ok(fopts) = crypto::decrypt_fopts(phy, &session_keys, fcnt_msb) {
+                        ui.label("FOpts:");
+                        ui.monospace(hex::encode_upper(fopts));
+                        ui.end_row();
+                    }
+                }
+
+                ui.label("FRMPayload:");
+                match crypto::decrypt_frm_payload(phy, &session_keys, fcnt_msb) {
+                    Ok(payload) => {
+                        ui.vertical(|ui| {
+                            ui.monospace(hex::encode_upper(&payload));
+                            if !payload.is_empty()
+                                && payload.iter().all(|b| b.is_ascii_graphic() || *b == b' ')
+                            {
+                                ui.monospace(String::from_utf8_lossy(&payload));
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        ui.weak(e.to_string());
+                    }
+                }
+                ui.end_row();
+            });
+    }
+}
+
+/// Grid of every field in `phy`. Returns the Devaddr if it was clicked.
+pub fn show_phy_payload(ui: &mut egui::Ui, phy: &PhyPayload) -> Option<String> {
+    let mut inspect = None;
+    egui::Grid::new("phy_payload_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("MType:");
+            ui.label(phy.mhdr.mtype.to_string());
+            ui.end_row();
+
+            ui.label("Major:");
+            match phy.mhdr.major {
+                0 => ui.label("LoRaWAN R1"),
+                major => ui.label(format!("{major} (RFU)")),
+            };
+            ui.end_row();
+
+            match &phy.payload {
+                MacPayload::JoinRequest(jr) => {
+                    ui.label("JoinEUI:");
+                    ui.monospace(format!("{:016X}", jr.join_eui));
+                    ui.end_row();
+
+                    ui.label("DevEUI:");
+                    ui.monospace(format!("{:016X}", jr.dev_eui));
+                    ui.end_row();
+
+                    ui.label("DevNonce:");
+                    ui.monospace(format!("{:04X} ({})", jr.dev_nonce, jr.dev_nonce));
+                    ui.end_row();
+                }
+                MacPayload::JoinAccept(encrypted) => {
+                    ui.label("Encrypted:");
+                    ui.monospace(hex::encode_upper(encrypted));
+                    ui.end_row();
+
+                    ui.label("CFList:");
+                    ui.label(if encrypted.len() > 16 { "Yes" } else { "No" });
+                    ui.end_row();
+                }
+                MacPayload::Data(data) => {
+                    let fhdr = &data.fhdr;
+                    ui.label("DevAddr:");
+                    let hex = format!("{:08X}", fhdr.dev_addr);
+                    if ui
+                        .button(&hex)
+                        .on_hover_text("Inspect in the NetID view")
+                        .clicked()
+                    {
+                        inspect = Some(hex);
+                    }
+                    match fhdr.devaddr() {
+                        Ok(d) => {
+                            let operator = operators::lookup_devaddr(&d)
+                                .map(|op| format!(", {}", op.name))
+                                .unwrap_or_default();
+                            ui.label(format!("NetID {}{operator}", d.net_id()));
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().warn_fg_color, e.to_string());
+                        }
+                    }
+                    ui.end_row();
+
+                    let fctrl = fhdr.fctrl;
+                    ui.label("FCtrl:");
+                    let mut bits = vec![];
+                    if fctrl.adr() {
+                        bits.push("ADR");
+                    }
+                    if fctrl.adr_ack_req() {
+                        bits.push("ADRACKReq");
+                    }
+                    if fctrl.ack() {
+                        bits.push("ACK");
+                    }
+                    if fctrl.class_b() {
+                        bits.push("ClassB");
+                    }
+                    if fctrl.f_pending() {
+                        bits.push("FPending");
+                    }
+                    ui.monospace(format!("{:02X}", fctrl.byte));
+                    ui.label(bits.join(", "));
+                    ui.end_row();
+
+                    ui.label("FCnt:");
+                    ui.label(fhdr.fcnt.to_string());
+                    ui.end_row();
+
+                    ui.label("FOpts:");
+                    ui.monospace(hex::encode_upper(&fhdr.fopts));
+                    ui.label(format!("{} bytes", fhdr.fopts.len()));
+                    ui.end_row();
+
+                    ui.label("FPort:");
+                    match data.fport {
+                        Some(0) => ui.label("0 (MAC commands)"),
+                        Some(fport) => ui.label(fport.to_string()),
+                        None => ui.weak("None"),
+                    };
+                    ui.end_row();
+
+                    ui.label("FRMPayload:");
+                    ui.monospace(hex::encode_upper(&data.frm_payload));
+                    ui.label(format!("{} bytes, encrypted", data.frm_payload.len()));
+                    ui.end_row();
+                }
+                MacPayload::RejoinRequest(bytes) | MacPayload::Proprietary(bytes) => {
+                    ui.label("Payload:");
+                    ui.monospace(hex::encode_upper(bytes));
+                    ui.end_row();
+                }
+            }
+
+            if let Some(mic) = phy.mic {
+                ui.label("MIC:");
+                ui.monospace(hex::encode_upper(mic));
+                ui.end_row();
+            }
+        });
+    inspect
+}
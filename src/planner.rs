@@ -0,0 +1,393 @@
+use crate::{
+    addressing::{DevaddrRange, NetID},
+    num_format::ToFormattedString,
+};
+use egui_extras::Column;
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    net_id: String,
+    requests: String,
+    allocated: String,
+    /// Why the last copy failed, until the next copy.
+    #[serde(skip)]
+    copy_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Allocated,
+    Planned,
+    DoesNotFit,
+    Overlaps,
+    OutsideNetId,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Slab {
+    name: String,
+    status: Status,
+    requested: Option<u32>,
+    size: Option<u64>,
+    start: Option<String>,
+    end: Option<String>,
+    prefix: Option<u32>,
+    note: String,
+    #[serde(skip)]
+    range: Option<DevaddrRange>,
+}
+
+struct Plan {
+    slabs: Vec<Slab>,
+    /// Devaddrs in the NetID covered by an existing or planned slab.
+    used: u64,
+}
+
+impl State {
+    pub fn main_view(&mut self, ui: &mut egui::Ui) {
+        let Self {
+            net_id,
+            requests,
+            allocated,
+            copy_error,
+        } = self;
+
+        ui.horizontal(|ui| {
+            ui.label("Net ID:");
+            ui.add(egui::TextEdit::singleline(net_id).hint_text("Net ID"));
+        });
+
+        ui.columns(2, |columns| {
+            columns[0].label("Requested blocks (name, size):");
+            columns[0].add(
+                egui::TextEdit::multiline(requests)
+                    .hint_text("acme, 1000\nglobex, 64")
+                    .desired_rows(4),
+            );
+            columns[1].label("Already allocated (name, start-end):");
+            columns[1].add(
+                egui::TextEdit::multiline(allocated)
+                    .hint_text("initech, 78000000-780003FF")
+                    .desired_rows(4),
+            );
+        });
+
+        let n = match NetID::new(net_id) {
+            Ok(n) => n,
+            Err(e) => {
+                if !net_id.trim().is_empty() {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+                return;
+            }
+        };
+
+        let (requests, mut errors) = parse_requests(requests);
+        let (allocated, allocated_errors) = parse_allocated(allocated);
+        errors.extend(allocated_errors);
+        for error in errors {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        let plan = plan(&n, &requests, &allocated);
+        let size = n.size() as u64;
+        let per = plan.used as f32 / size as f32;
+        ui.add(egui::ProgressBar::new(per).text(format!(
+            "{} of {} Devaddrs used ({:.2}%)",
+            plan.used.to_formatted_string(),
+            size.to_formatted_string(),
+            per * 100.0
+        )));
+
+        ui.horizontal(|ui| {
+            let mut copied = None;
+            if ui.button("Copy JSON").clicked() {
+                copied = Some(serde_json::to_string_pretty(&plan.slabs).map_err(|e| e.to_string()));
+            }
+            if ui.button("Copy CSV").clicked() {
+                copied = Some(to_csv(&plan.slabs).map_err(|e| e.to_string()));
+            }
+            match copied {
+                Some(Ok(text)) => {
+                    ui.output_mut(|o| o.copied_text = text);
+                    *copy_error = None;
+                }
+                Some(Err(e)) => *copy_error = Some(e),
+                None => (),
+            }
+        });
+        if let Some(e) = copy_error {
+            ui.colored_label(ui.visuals().error_fg_color, e.as_str());
+        }
+
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(400.0)
+            .columns(Column::auto().resizable(true), 6)
+            .column(Column::remainder())
+            .header(20.0, |mut header| {
+                for text in [
+                    "Name",
+                    "Status",
+                    "Requested",
+                    "Slab",
+                    "Start",
+                    "End",
+                    "Note",
+                ] {
+                    header.col(|ui| {
+                        ui.strong(text);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(18.0, plan.slabs.len(), |mut row| {
+                    let slab = &plan.slabs[row.index()];
+                    row.col(|ui| {
+                        ui.label(&slab.name);
+                    });
+                    row.col(|ui| {
+                        let text = format!("{:?}", slab.status);
+                        match slab.status {
+                            Status::Allocated | Status::Planned => ui.label(text),
+                            _ => ui.colored_label(ui.visuals().warn_fg_color, text),
+                        };
+                    });
+                    row.col(|ui| {
+                        if let Some(requested) = slab.requested {
+                            ui.label(requested.to_formatted_string());
+                        }
+                    });
+                    row.col(|ui| {
+                        if let Some(size) = slab.size {
+                            let prefix = slab.prefix.map(|p| format!(" (/{p})"));
+                            ui.label(format!(
+                                "{}{}",
+                                size.to_formatted_string(),
+                                prefix.unwrap_or_default()
+                            ));
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.monospace(slab.start.as_deref().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        ui.monospace(slab.end.as_deref().unwrap_or_default());
+                    });
+                    row.col(|ui| {
+                        ui.label(&slab.note);
+                    });
+                });
+            });
+    }
+}
+
+impl Slab {
+    fn new(
+        name: &str,
+        status: Status,
+        requested: Option<u32>,
+        range: Option<DevaddrRange>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            requested,
+            size: range.map(|r| r.size()),
+            start: range.map(|r| format!("{:08X}", r.start())),
+            end: range.map(|r| format!("{:08X}", r.end())),
+            prefix: range.and_then(|r| r.prefix_len()),
+            note: String::new(),
+            range,
+        }
+    }
+}
+
+/// Lines of `name, size`, the name is optional.
+fn parse_requests(input: &str) -> (Vec<(String, u32)>, Vec<String>) {
+    let mut requests = vec![];
+    let mut errors = vec![];
+    for (idx, line) in lines(input) {
+        let (name, size) = split_name(line, idx);
+        match size.replace('_', "").parse::<u32>() {
+            Ok(0) => errors.push(format!("line {idx}: size must be more than 0")),
+            Ok(size) => requests.push((name, size)),
+            Err(e) => errors.push(format!("line {idx}: {size:?} {e}")),
+        }
+    }
+    (requests, errors)
+}
+
+/// Lines of `name, start-end`, the name is optional.
+fn parse_allocated(input: &str) -> (Vec<(String, DevaddrRange)>, Vec<String>) {
+    let mut allocated = vec![];
+    let mut errors = vec![];
+    for (idx, line) in lines(input) {
+        let (name, range) = split_name(line, idx);
+        match range.parse() {
+            Ok(range) => allocated.push((name, range)),
+            Err(e) => errors.push(format!("line {idx}: {e}")),
+        }
+    }
+    (allocated, errors)
+}
+
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn split_name(line: &str, idx: usize) -> (String, &str) {
+    match line.rsplit_once(',') {
+        Some((name, value)) => (name.trim().to_string(), value.trim()),
+        None => (format!("line {idx}"), line),
+    }
+}
+
+/// Carve a power of two sized, aligned slab out of `net_id` for every
+/// request, working around what's already `allocated`.
+fn plan(net_id: &NetID, requests: &[(String, u32)], allocated: &[(String, DevaddrRange)]) -> Plan {
+    let space = net_id.range();
+    let mut slabs = vec![];
+    let mut occupied = vec![];
+
+    for (idx, (name, range)) in allocated.iter().enumerate() {
+        let overlapping = allocated[..idx]
+            .iter()
+            .find(|(_, other)| other.overlaps(range));
+
+        // The status is the worse problem, the note lists all of them.
+        let mut slab = Slab::new(name, Status::Allocated, None, Some(*range));
+        let mut notes = vec![];
+        if let Some((other, _)) = overlapping {
+            slab.status = Status::Overlaps;
+            notes.push(format!("overlaps {other}"));
+        }
+        if range.intersection(&space) != Some(*range) {
+            slab.status = Status::OutsideNetId;
+            notes.push(format!("not entirely in {space}"));
+        }
+        if slab.prefix.is_none() {
+            notes.push("not prefix aligned".to_string());
+        }
+        slab.note = notes.join(", ");
+        occupied.extend(range.intersection(&space));
+        slabs.push(slab);
+    }
+
+    // Biggest first so the smaller slabs fill in around them.
+    let mut order: Vec<usize> = (0..requests.len()).collect();
+    order.sort_by_key(|&idx| std::cmp::Reverse(requests[idx].1));
+
+    let mut planned = vec![None; requests.len()];
+    for idx in order {
+        let size = (requests[idx].1 as u64).next_power_of_two();
+        planned[idx] = find_slot(&space, &occupied, size);
+        occupied.extend(planned[idx]);
+    }
+
+    for ((name, requested), range) in requests.iter().zip(planned) {
+        let status = match range {
+            Some(_) => Status::Planned,
+            None => Status::DoesNotFit,
+        };
+        slabs.push(Slab::new(name, status, Some(*requested), range));
+    }
+
+    slabs.sort_by_key(|slab| slab.range.map_or(u32::MAX, |r| r.start()));
+
+    Plan {
+        slabs,
+        used: DevaddrRange::merge(occupied).iter().map(|r| r.size()).sum(),
+    }
+}
+
+/// Lowest slab of `size` in `space`, aligned to `size`, that doesn't overlap
+/// anything `occupied`.
+fn find_slot(space: &DevaddrRange, occupied: &[DevaddrRange], size: u64) -> Option<DevaddrRange> {
+    let mut start = (space.start() as u64).next_multiple_of(size);
+    loop {
+        let end = start + size - 1;
+        if end > space.end() as u64 {
+            return None;
+        }
+        let candidate = DevaddrRange::new(start as u32, end as u32).ok()?;
+        let blocking_end = occupied
+            .iter()
+            .filter(|r| r.overlaps(&candidate))
+            .map(|r| r.end())
+            .max();
+        match blocking_end {
+            Some(blocking_end) => start = (blocking_end as u64 + 1).next_multiple_of(size),
+            None => return Some(candidate),
+        }
+    }
+}
+
+fn to_csv(slabs: &[Slab]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for slab in slabs {
+        writer.serialize(slab)?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_around_allocations() {
+        // 00003C has 78000000-79FFFFFF
+        let n = NetID::new("00003C").unwrap();
+        let (allocated, errors) = parse_allocated(
+            "initech, 78000000-780003FF\nhooli, 78000200-780002FF\noops, 7A000000-7A0000FF",
+        );
+        assert!(errors.is_empty());
+        let (requests, errors) = parse_requests("acme, 1000\nglobex, 64\n300");
+        assert!(errors.is_empty());
+
+        let plan = plan(&n, &requests, &allocated);
+        let find = |name: &str| plan.slabs.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("hooli").status, Status::Overlaps);
+        assert_eq!(find("oops").status, Status::OutsideNetId);
+
+        let acme = find("acme");
+        assert_eq!(acme.status, Status::Planned);
+        assert_eq!(acme.size, Some(1024));
+        assert_eq!(acme.start.as_deref(), Some("78000400"));
+        assert_eq!(acme.prefix, Some(22));
+
+        // Fits in after acme, the 300 request rounded up to 512 goes first.
+        assert_eq!(find("line 3").start.as_deref(), Some("78000800"));
+        assert_eq!(find("globex").start.as_deref(), Some("78000A00"));
+
+        assert_eq!(plan.used, 1024 + 1024 + 512 + 64);
+    }
+
+    #[test]
+    fn outside_and_overlapping() {
+        let n = NetID::new("00003C").unwrap();
+        let (allocated, _) = parse_allocated("oops, 7A000000-7A0000FF\nedge, 79FFFF00-7A00000F");
+        let plan = plan(&n, &[], &allocated);
+        let edge = plan.slabs.iter().find(|s| s.name == "edge").unwrap();
+        assert_eq!(edge.status, Status::OutsideNetId);
+        assert!(edge.note.contains("overlaps oops"), "{}", edge.note);
+        assert!(edge.note.contains("not entirely in"), "{}", edge.note);
+    }
+
+    #[test]
+    fn too_big() {
+        let n = NetID::new("C00053").unwrap();
+        let (requests, _) = parse_requests("big, 2048");
+        let plan = plan(&n, &requests, &[]);
+        assert_eq!(plan.slabs[0].status, Status::DoesNotFit);
+        assert_eq!(plan.used, 0);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fmt, str::FromStr};
+
+/// LoRaWAN regions as Helium names them in roaming tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    US915,
+    EU868,
+    AS923_1,
+    AS923_2,
+    AS923_3,
+    AS923_4,
+    AU915,
+    CN470,
+    KR920,
+    IN865,
+    EU433,
+    CN779,
+    RU864,
+}
+
+/// The parts of the LoRaWAN Regional Parameters worth knowing at a glance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionalParams {
+    /// Lowest and highest frequency of the band, in MHz.
+    pub band_mhz: (f32, f32),
+    pub rx2_freq_mhz: f32,
+    /// The RX2 and join default data rate.
+    pub rx2_data_rate: &'static str,
+    pub max_eirp_dbm: f32,
+    /// Fraction of the time a device may transmit, where one is imposed.
+    pub duty_cycle: Option<f32>,
+    /// Longest a single uplink may be on air by default, in ms.
+    pub dwell_time_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRegion {
+    pub input: String,
+    /// A known region the input starts with, like EU868 for `EU868_A`.
+    pub closest: Option<Region>,
+}
+
+impl Region {
+    pub const ALL: [Region; 13] = [
+        Self::US915,
+        Self::EU868,
+        Self::AS923_1,
+        Self::AS923_2,
+        Self::AS923_3,
+        Self::AS923_4,
+        Self::AU915,
+        Self::CN470,
+        Self::KR920,
+        Self::IN865,
+        Self::EU433,
+        Self::CN779,
+        Self::RU864,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::US915 => "US915",
+            Self::EU868 => "EU868",
+            Self::AS923_1 => "AS923_1",
+            Self::AS923_2 => "AS923_2",
+            Self::AS923_3 => "AS923_3",
+            Self::AS923_4 => "AS923_4",
+            Self::AU915 => "AU915",
+            Self::CN470 => "CN470",
+            Self::KR920 => "KR920",
+            Self::IN865 => "IN865",
+            Self::EU433 => "EU433",
+            Self::CN779 => "CN779",
+            Self::RU864 => "RU864",
+        }
+    }
+
+    pub fn params(&self) -> RegionalParams {
+        let params =
+            |band_mhz, rx2_freq_mhz, rx2_data_rate, max_eirp_dbm, duty_cycle| RegionalParams {
+                band_mhz,
+                rx2_freq_mhz,
+                rx2_data_rate,
+                max_eirp_dbm,
+                duty_cycle,
+                dwell_time_ms: None,
+            };
+        // FCC rules, and AS923 devices until told otherwise by TxParamSetupReq.
+        let dwell = |params: RegionalParams| RegionalParams {
+            dwell_time_ms: Some(400.0),
+            ..params
+        };
+        match self {
+            Self::US915 => dwell(params((902.0, 928.0), 923.3, "SF12BW500", 30.0, None)),
+            Self::EU868 => params((863.0, 870.0), 869.525, "SF12BW125", 16.0, Some(0.01)),
+            Self::AS923_1 => dwell(params((915.0, 928.0), 923.2, "SF10BW125", 16.0, None)),
+            Self::AS923_2 => dwell(params((920.0, 923.0), 921.4, "SF10BW125", 16.0, None)),
+            Self::AS923_3 => dwell(params((915.0, 921.0), 916.6, "SF10BW125", 16.0, None)),
+            Self::AS923_4 => dwell(params((917.0, 920.0), 917.3, "SF10BW125", 16.0, None)),
+            Self::AU915 => params((915.0, 928.0), 923.3, "SF12BW500", 30.0, None),
+            Self::CN470 => params((470.0, 510.0), 505.3, "SF12BW125", 19.15, None),
+            Self::KR920 => params((920.9, 923.3), 921.9, "SF12BW125", 14.0, None),
+            Self::IN865 => params((865.0, 867.0), 866.55, "SF10BW125", 30.0, None),
+            Self::EU433 => params((433.175, 434.665), 434.665, "SF12BW125", 12.15, Some(0.01)),
+            Self::CN779 => params((779.0, 787.0), 786.0, "SF12BW125", 12.15, Some(0.01)),
+            Self::RU864 => params((864.0, 870.0), 869.1, "SF12BW125", 16.0, Some(0.01)),
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = UnknownRegion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if let Some(region) = Self::ALL.iter().find(|r| r.name() == input) {
+            return Ok(*region);
+        }
+        // Prefer the longest name, then the first listed, so plain `AS923`
+        // points at AS923_1.
+        let upper = input.to_uppercase();
+        let closest = Self::ALL
+            .iter()
+            .filter(|r| upper.starts_with(r.name()) || r.name().starts_with(&upper))
+            .min_by_key(|r| std::cmp::Reverse(r.name().len()))
+            .copied()
+            .filter(|_| !upper.is_empty());
+        Err(UnknownRegion {
+            input: input.to_string(),
+            closest,
+        })
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for UnknownRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown region {:?}", self.input)?;
+        if let Some(closest) = self.closest {
+            write!(
+                f,
+                ", closest is {closest}, check the gateway's configuration"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownRegion {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_regions() {
+        for region in Region::ALL {
+            assert_eq!(region.name().parse(), Ok(region));
+        }
+        assert_eq!(Region::EU868.params().duty_cycle, Some(0.01));
+        assert_eq!(Region::US915.params().dwell_time_ms, Some(400.0));
+
+        let e = "EU868_A".parse::<Region>().unwrap_err();
+        assert_eq!(e.closest, Some(Region::EU868));
+        assert_eq!(
+            "AS923".parse::<Region>().unwrap_err().closest,
+            Some(Region::AS923_1)
+        );
+        assert_eq!(
+            "us915".parse::<Region>().unwrap_err().closest,
+            Some(Region::US915)
+        );
+        assert_eq!("XX123".parse::<Region>().unwrap_err().closest, None);
+    }
+}
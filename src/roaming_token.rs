@@ -1,116 +1,889 @@
+use crate::{
+    helium_address::{self, animal_name},
+    region::Region,
+    token_history,
+};
+use std::collections::BTreeMap;
+
 #[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
 pub struct State {
     input_token: String,
+    encoder: Encoder,
+    converter: helium_address::State,
+    /// b58 gateway pubkeys, so an animal name can be turned back into one.
+    known_gateways: Vec<String>,
+    /// Names for route IDs, keyed by [`route_key`].
+    route_labels: BTreeMap<String, String>,
+    history: token_history::State,
+    /// IANA name, packet times are also shown in this zone.
+    timezone: String,
+    /// When the LNS got the uplink, RFC3339 or epoch ms.
+    received_at: String,
 }
 
-#[derive(Debug)]
-struct Token {
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct Encoder {
     region: String,
-    packet_time: u64,
+    use_now: bool,
+    /// RFC3339 or epoch milliseconds, used when not `use_now`.
+    packet_time: String,
     route_id: String,
-    b58: String,
-    animal_name: String,
+    /// b58 pubkey or the animal name of a known gateway.
+    gateway: String,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self {
+            region: "US915".to_string(),
+            use_now: true,
+            packet_time: Default::default(),
+            route_id: Default::default(),
+            gateway: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Token {
+    pub region: String,
+    pub packet_time: u64,
+    pub route_id: String,
+    pub pubkey: Pubkey,
+    pub b58: String,
+    pub animal_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    EccCompact,
+    Ed25519,
+}
+
+/// A pubkeybin is a `network << 4 | key_type` byte followed by the key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pubkey {
+    pub network: Network,
+    pub key_type: KeyType,
+    pub key: Vec<u8>,
+}
+
+/// How far ahead of us a packet time can be before it's a clock problem.
+const CLOCK_SKEW_MS: u64 = 60_000;
+/// 2020-01-01T00:00:00Z
+const OLDEST_PLAUSIBLE_MS: u64 = 1_577_836_800_000;
+
+/// Whatever could be read out of a token before it stopped making sense.
+#[derive(Debug, Default)]
+struct PartialToken {
+    region: Option<String>,
+    packet_time: Option<u64>,
+    route_id: Option<String>,
+    pubkeybin: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenError {
+    Empty,
+    NotHex(hex::FromHexError),
+    /// The `::` that should come after `field` isn't there.
+    MissingSeparator {
+        field: &'static str,
+    },
+    NotUtf8 {
+        field: &'static str,
+    },
+    TimeNotNumeric(String),
+    /// A number of milliseconds too big to be a date.
+    TimeOutOfRange(u64),
+    /// Too short to hold a type byte and any key.
+    PubkeyLength(usize),
+    /// Only mainnet (0) and testnet (1) exist.
+    PubkeyNetwork(u8),
+    /// Only ecc_compact (0) and ed25519 (1) keys are used by gateways.
+    PubkeyKeyType(u8),
+}
+
+impl KeyType {
+    /// Bytes in a key of this type, not counting the type byte.
+    pub fn key_len(self) -> usize {
+        match self {
+            // Just the x coordinate, the y is always the even one.
+            Self::EccCompact => 32,
+            Self::Ed25519 => 32,
+        }
+    }
+}
+
+impl Pubkey {
+    pub fn from_bin(bin: &[u8]) -> Result<Self, TokenError> {
+        let [type_byte, key @ ..] = bin else {
+            return Err(TokenError::PubkeyLength(0));
+        };
+        if key.is_empty() {
+            return Err(TokenError::PubkeyLength(bin.len()));
+        }
+        let network = match type_byte >> 4 {
+            0 => Network::Mainnet,
+            1 => Network::Testnet,
+            other => return Err(TokenError::PubkeyNetwork(other)),
+        };
+        let key_type = match type_byte & 0x0F {
+            0 => KeyType::EccCompact,
+            1 => KeyType::Ed25519,
+            other => return Err(TokenError::PubkeyKeyType(other)),
+        };
+        Ok(Self {
+            network,
+            key_type,
+            key: key.to_vec(),
+        })
+    }
+
+    /// Whether the key is the wrong size for its type, usually a sign the
+    /// bytes came from somewhere other than a gateway.
+    pub fn length_mismatch(&self) -> bool {
+        self.key.len() != self.key_type.key_len()
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mainnet => write!(f, "mainnet"),
+            Self::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+impl std::fmt::Display for KeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EccCompact => write!(f, "ecc_compact"),
+            Self::Ed25519 => write!(f, "ed25519"),
+        }
+    }
 }
 
 impl State {
     pub fn side_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Known Gateways");
+        let mut remove = None;
+        egui::Grid::new("known_gateways").show(ui, |ui| {
+            for (idx, b58) in self.known_gateways.iter().enumerate() {
+                ui.label(animal_name(b58)).on_hover_text(b58);
+                if ui.small_button("🗑").on_hover_text("Forget").clicked() {
+                    remove = Some(idx);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = remove {
+            self.known_gateways.remove(idx);
+        }
+        if self.known_gateways.is_empty() {
+            ui.weak("Remember a gateway from a decoded token to encode by animal name.");
+        }
+        ui.separator();
+
+        ui.heading("Route Labels");
+        let mut remove = None;
+        egui::Grid::new("route_labels").show(ui, |ui| {
+            for (route_id, label) in self.route_labels.iter() {
+                ui.label(label).on_hover_text(route_id);
+                if ui.small_button("🗑").on_hover_text("Forget").clicked() {
+                    remove = Some(route_id.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(route_id) = remove {
+            self.route_labels.remove(&route_id);
+        }
+        if self.route_labels.is_empty() {
+            ui.weak("Label a route ID from a decoded token.");
+        }
+        ui.separator();
+
         ui.heading("Links");
         ui.hyperlink_to("Roaming Token Src", "https://github.com/helium/helium-packet-router/blob/main/src/protocols/http/hpr_http_roaming.erl#L414-L446");
     }
 
     pub fn main_view(&mut self, ui: &mut egui::Ui) {
-        let Self { input_token } = self;
+        let Self {
+            input_token,
+            encoder,
+            converter,
+            known_gateways,
+            route_labels,
+            history,
+            timezone,
+            received_at,
+        } = self;
 
         let token_field = egui::TextEdit::singleline(input_token).hint_text("FNSULToken");
-        ui.horizontal(|ui| {
-            ui.label("FNSULToken:");
-            ui.add(token_field);
-        });
+        let (done_editing, save) = ui
+            .horizontal(|ui| {
+                ui.label("FNSULToken:");
+                let done_editing = ui.add(token_field).lost_focus();
+                (done_editing, ui.button("Save to history").clicked())
+            })
+            .inner;
 
-        if let Some(token) = parse_token(input_token) {
-            egui::Grid::new("roaming-token-grid").show(ui, |ui| {
+        let mut partial = PartialToken::default();
+        let parsed = read_token(input_token, &mut partial);
+        match &parsed {
+            // A key of the wrong length is most likely still being typed.
+            Ok(token) if !token.pubkey.length_mismatch() => {
+                if done_editing || save {
+                    history.record(input_token);
+                }
+            }
+            Ok(_) => (),
+            Err(e) => {
+                if !input_token.trim().is_empty() {
+                    ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                }
+            }
+        }
+
+        egui::Grid::new("roaming-token-grid").show(ui, |ui| {
+            if let Some(region) = partial.region {
                 ui.label("Region:");
-                ui.label(token.region);
-                ui.end_row();
+                ui.label(&region);
+                match region.parse::<Region>() {
+                    Ok(region) => {
+                        ui.end_row();
+                        show_region_params(ui, region);
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, e.to_string());
+                        ui.end_row();
+                    }
+                }
+            }
 
-                let time = chrono::DateTime::from_timestamp_millis(token.packet_time as i64)
-                    .expect("valid timestamp");
-                let date = humantime::format_rfc3339_millis(time.into());
-                let mut time_formatter = timeago::Formatter::new();
-                time_formatter.num_items(4);
-                let ago = time_formatter.convert_chrono(time, chrono::Utc::now());
+            if let Some(packet_time) = partial.packet_time {
                 ui.label("Packet Time:");
-                ui.label(format!("{} ({date})", token.packet_time));
-                ui.label(ago);
+                match chrono::DateTime::from_timestamp_millis(packet_time as i64) {
+                    Some(time) => {
+                        let date = humantime::format_rfc3339_millis(time.into());
+                        let mut time_formatter = timeago::Formatter::new();
+                        time_formatter.num_items(4);
+                        let ago = time_formatter.convert_chrono(time, chrono::Utc::now());
+                        ui.label(format!("{packet_time} ({date})"));
+                        ui.label(ago);
+                        ui.end_row();
+
+                        ui.label("Timezone:");
+                        ui.add(
+                            egui::TextEdit::singleline(timezone)
+                                .hint_text("UTC")
+                                .desired_width(160.0),
+                        );
+                        let zone = if timezone.trim().is_empty() {
+                            Ok(chrono_tz::UTC)
+                        } else {
+                            timezone.trim().parse::<chrono_tz::Tz>()
+                        };
+                        match zone {
+                            Ok(zone) => ui.label(
+                                time.with_timezone(&zone)
+                                    .format("%Y-%m-%d %H:%M:%S%.3f %Z (%:z)")
+                                    .to_string(),
+                            ),
+                            Err(_) => ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                "not an IANA timezone, like America/Chicago",
+                            ),
+                        };
+                    }
+                    None => {
+                        ui.label(packet_time.to_string());
+                    }
+                }
                 ui.end_row();
 
+                let now = chrono::Utc::now().timestamp_millis() as u64;
+                if let Some(warning) = clock_warning(packet_time, now) {
+                    ui.label("");
+                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                    ui.end_row();
+                }
+
+                ui.label("Received At:");
+                ui.add(
+                    egui::TextEdit::singleline(received_at)
+                        .hint_text("LNS receive time, RFC3339 or epoch ms")
+                        .desired_width(160.0),
+                );
+                if !received_at.trim().is_empty() {
+                    match parse_time(received_at) {
+                        Ok(received) => {
+                            let latency = received as i128 - packet_time as i128;
+                            if latency < 0 {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!("{latency} ms, received before it was sent"),
+                                );
+                            } else {
+                                ui.label(format!("{latency} ms latency"));
+                            }
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+
+            if let Some(route_id) = partial.route_id {
+                let key = route_key(&route_id);
                 ui.label("Route ID:");
-                ui.label(token.route_id);
+                match route_labels.get(&key) {
+                    Some(label) => ui.strong(label).on_hover_text(&route_id),
+                    None => ui.label(&route_id),
+                };
+                ui.end_row();
+
+                ui.label("UUID:");
+                match uuid::Uuid::parse_str(&route_id) {
+                    Ok(uuid) => {
+                        ui.label(describe_uuid(&uuid));
+                        if uuid.hyphenated().to_string() != route_id {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("not in canonical form, {}", uuid.hyphenated()),
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("not a UUID: {e}"));
+                    }
+                }
                 ui.end_row();
 
+                ui.label("Label:");
+                let mut label = route_labels.get(&key).cloned().unwrap_or_default();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut label).hint_text("Acme LNS (prod)"))
+                    .changed()
+                {
+                    if label.trim().is_empty() {
+                        route_labels.remove(&key);
+                    } else {
+                        route_labels.insert(key, label);
+                    }
+                }
+                ui.end_row();
+            }
+
+            if let Some(pubkeybin) = &partial.pubkeybin {
+                ui.label("Pubkeybin:");
+                ui.monospace(hex::encode_upper(pubkeybin));
+                ui.label(format!("{} bytes", pubkeybin.len()));
+                ui.end_row();
+            }
+
+            if let Ok(token) = &parsed {
+                let pubkey = &token.pubkey;
+                ui.label("Network:");
+                match pubkey.network {
+                    Network::Mainnet => ui.label(pubkey.network.to_string()),
+                    Network::Testnet => {
+                        ui.colored_label(ui.visuals().warn_fg_color, pubkey.network.to_string())
+                    }
+                };
+                ui.end_row();
+
+                ui.label("Key Type:");
+                ui.label(pubkey.key_type.to_string());
+                if pubkey.length_mismatch() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!(
+                            "{} keys are {} bytes, this one is {}",
+                            pubkey.key_type,
+                            pubkey.key_type.key_len(),
+                            pubkey.key.len()
+                        ),
+                    );
+                }
+                ui.end_row();
+
+                ui.label("Public Key:");
+                ui.monospace(hex::encode_upper(&pubkey.key));
+                ui.label(format!("{} bytes", pubkey.key.len()));
+                ui.end_row();
+            }
+
+            if let Ok(token) = parsed {
                 ui.label("Gateway:");
-                ui.label(token.b58);
+                ui.label(&token.b58);
                 ui.label(token.animal_name);
+                if !known_gateways.contains(&token.b58) && ui.button("Remember").clicked() {
+                    known_gateways.push(token.b58);
+                }
                 ui.end_row();
+            }
+        });
+
+        ui.separator();
+        ui.collapsing("History", |ui| {
+            history.main_view(ui, input_token, route_labels)
+        });
+
+        ui.collapsing("Encode Token", |ui| {
+            encoder.main_view(ui, known_gateways, route_labels, input_token)
+        });
+
+        ui.collapsing("Address Converter", |ui| {
+            converter.main_view(ui, known_gateways)
+        });
+    }
+}
+
+fn show_region_params(ui: &mut egui::Ui, region: Region) {
+    let params = region.params();
+    ui.label("Band:");
+    ui.label(format!("{} - {} MHz", params.band_mhz.0, params.band_mhz.1));
+    ui.end_row();
+
+    ui.label("RX2:");
+    ui.label(format!(
+        "{} MHz {}",
+        params.rx2_freq_mhz, params.rx2_data_rate
+    ));
+    ui.end_row();
+
+    ui.label("Max EIRP:");
+    ui.label(format!("{} dBm", params.max_eirp_dbm));
+    ui.end_row();
+
+    if let Some(duty_cycle) = params.duty_cycle {
+        ui.label("Duty Cycle:");
+        ui.label(format!("{}%", duty_cycle * 100.0));
+        ui.end_row();
+    }
+
+    if let Some(dwell_time_ms) = params.dwell_time_ms {
+        ui.label("Dwell Time:");
+        ui.label(format!("{dwell_time_ms} ms"));
+        ui.end_row();
+    }
+}
+
+impl Encoder {
+    /// A good token can be sent to `input_token` to be decoded.
+    fn main_view(
+        &mut self,
+        ui: &mut egui::Ui,
+        known_gateways: &[String],
+        route_labels: &BTreeMap<String, String>,
+        input_token: &mut String,
+    ) {
+        egui::Grid::new("roaming-token-encoder").show(ui, |ui| {
+            ui.label("Region:");
+            ui.text_edit_singleline(&mut self.region);
+            ui.end_row();
+
+            ui.label("Packet Time:");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.use_now, "Now");
+                ui.add_enabled(
+                    !self.use_now,
+                    egui::TextEdit::singleline(&mut self.packet_time)
+                        .hint_text("2024-06-01T12:00:00Z or epoch ms"),
+                );
             });
+            ui.end_row();
+
+            ui.label("Route ID:");
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.route_id);
+                let selected = route_labels
+                    .get(&route_key(&self.route_id))
+                    .map_or("Labeled", String::as_str);
+                egui::ComboBox::from_id_source("encoder_route")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (route_id, label) in route_labels {
+                            if ui.button(label).clicked() {
+                                self.route_id.clone_from(route_id);
+                            }
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("Gateway:");
+            egui::ComboBox::from_id_source("encoder_gateway")
+                .selected_text(&self.gateway)
+                .show_ui(ui, |ui| {
+                    for b58 in known_gateways {
+                        let name = animal_name(b58);
+                        ui.selectable_value(&mut self.gateway, name.clone(), name);
+                    }
+                });
+            ui.end_row();
+
+            ui.label("");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.gateway)
+                    .hint_text("b58 pubkey or animal name"),
+            );
+            ui.end_row();
+        });
+
+        let packet_time = if self.use_now {
+            Ok(chrono::Utc::now().timestamp_millis() as u64)
         } else {
-            ui.label("Unparseable Token");
+            parse_time(&self.packet_time)
+        };
+        let token = packet_time.and_then(|packet_time| {
+            let pubkeybin = gateway_pubkeybin(&self.gateway, known_gateways)?;
+            Ok(encode_token(
+                &self.region,
+                packet_time,
+                &self.route_id,
+                &pubkeybin,
+            ))
+        });
+
+        match token {
+            Ok(token) => {
+                // A `::` in the region or route ID shifts every field after it.
+                if let Err(e) = parse_token(&token) {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("Will not decode: {e}"));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = token.clone());
+                    }
+                    if ui.button("Decode").clicked() {
+                        input_token.clone_from(&token);
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::multiline(&mut token.as_str())
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY),
+                );
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
         }
     }
 }
 
+/// RFC3339, or milliseconds since the epoch.
+fn parse_time(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    if let Ok(millis) = input.parse::<u64>() {
+        return Ok(millis);
+    }
+    let time =
+        chrono::DateTime::parse_from_rfc3339(input).map_err(|e| format!("time {input:?}: {e}"))?;
+    u64::try_from(time.timestamp_millis()).map_err(|_| format!("time {input:?} is before 1970"))
+}
+
+/// Gateways without a good clock send times that are ahead of us, or from
+/// long before Helium roaming existed.
+fn clock_warning(packet_time: u64, now: u64) -> Option<String> {
+    if packet_time > now + CLOCK_SKEW_MS {
+        let ahead = std::time::Duration::from_millis(packet_time - now);
+        return Some(format!(
+            "{} in the future, the gateway clock is ahead",
+            humantime::format_duration(ahead)
+        ));
+    }
+    if packet_time < OLDEST_PLAUSIBLE_MS {
+        return Some("before 2020, the gateway clock was probably never set".to_string());
+    }
+    None
+}
+
+/// The pubkeybin of a b58 pubkey, or of a known gateway with this animal name.
+fn gateway_pubkeybin(input: &str, known_gateways: &[String]) -> Result<Vec<u8>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("no gateway".to_string());
+    }
+    if let Ok(pubkeybin) = helium_address::from_b58(input) {
+        return Ok(pubkeybin);
+    }
+
+    let b58 = helium_address::find_by_animal_name(input, known_gateways)
+        .ok_or_else(|| format!("{input:?} is not a b58 pubkey or a known gateway"))?;
+    gateway_pubkeybin(b58, &[])
+}
+
+/// Route IDs are labeled by their canonical UUID when they are one, so the
+/// label sticks no matter how the UUID is written.
+pub fn route_key(route_id: &str) -> String {
+    match uuid::Uuid::parse_str(route_id.trim()) {
+        Ok(uuid) => uuid.hyphenated().to_string(),
+        Err(_) => route_id.trim().to_string(),
+    }
+}
+
+fn describe_uuid(uuid: &uuid::Uuid) -> String {
+    let kind = match uuid.get_version_num() {
+        _ if uuid.is_nil() => return "nil UUID".to_string(),
+        1 => "time based",
+        2 => "DCE security",
+        3 => "MD5 name based",
+        4 => "random",
+        5 => "SHA-1 name based",
+        6 => "reordered time based",
+        7 => "Unix time based",
+        8 => "custom",
+        _ => "unknown version",
+    };
+    format!("v{} ({kind})", uuid.get_version_num())
+}
+
+/// `0x` prefixed hex of `region::packet_time::route_id::pubkeybin`.
+pub fn encode_token(region: &str, packet_time: u64, route_id: &str, pubkeybin: &[u8]) -> String {
+    let mut token = format!("{region}::{packet_time}::{route_id}::").into_bytes();
+    token.extend_from_slice(pubkeybin);
+    format!("0x{}", hex::encode_upper(token))
+}
+
 #[test]
 fn test_parse_token() {
     //let input = "0x55533931353A3A313731373836373039363937383A3A313A3A01B9369F0B077DA6E65CA5D895565F507D912F32B190066F2DFD9520227A36AAC0".to_string();
     let input = "0x45553836383A3A323638393537393432343A3A31323739363333652D303661302D313165652D393839642D6637316363643537613231383A3A0072C4AE468379170A7F7955A4375C084A8327CAE9ACF0432DC91CFF2E66512257".to_string();
     let token = parse_token(&input);
     println!("{token:?}");
+    assert!(token.is_ok());
+}
+
+pub fn parse_token(input: &str) -> Result<Token, TokenError> {
+    read_token(input, &mut PartialToken::default())
 }
 
-fn parse_token(input: &str) -> Option<Token> {
+/// Parse `input`, filling in `partial` with each field as it's read so
+/// there's something to show when a later field is broken.
+fn read_token(input: &str, partial: &mut PartialToken) -> Result<Token, TokenError> {
     // remove preceding 0x if it exists
-    let input = input.trim_start_matches("0x");
+    let input = input.trim().trim_start_matches("0x");
     if input.is_empty() {
-        return None;
+        return Err(TokenError::Empty);
     }
 
-    let decoded = hex::decode(input).ok()?;
+    let decoded = hex::decode(input).map_err(TokenError::NotHex)?;
 
     // region::packet_time::route_id::pubkeybin
-    let re = regex::bytes::Regex::new(
-        "(?<region>.*)::(?<packet_time>.*)::(?<route_id>.*)::(?<pubkeybin>(?-u:.)*)",
-    )
-    .ok()?;
-    let caps = re.captures(&decoded)?;
-
-    let region_bytes = caps.name("region")?.as_bytes();
-    let region = String::from_utf8(region_bytes.to_vec()).ok()?;
-
-    let packet_time_bytes = caps.name("packet_time")?.as_bytes();
-    let packet_time_str = String::from_utf8(packet_time_bytes.to_vec()).ok()?;
-    let packet_time = packet_time_str.parse::<u64>().ok()?;
-
-    let route_id_bytes = caps.name("route_id")?.as_bytes();
-    let route_id = String::from_utf8(route_id_bytes.to_vec()).ok()?;
-
-    // Regex may not grab all bytes of pubkey, get from start to the end manually.
-    let pubkey_cap = &caps.name("pubkeybin")?;
-    let pubkey_bytes = &decoded[pubkey_cap.start()..];
-    // Taken from helium-crypto PubkeyBinary Display trait
-    let mut pubkey = vec![0u8; pubkey_bytes.len() + 1];
-    pubkey[1..].copy_from_slice(pubkey_bytes);
-    let b58 = bs58::encode(&pubkey).with_check().into_string();
-
-    let animal_name = b58
-        .parse::<angry_purple_tiger::AnimalName>()
-        .expect("animal_name")
-        .to_string();
-
-    Some(Token {
+    // The pubkeybin is raw bytes and may contain `::` itself, so only split
+    // off the first three fields.
+    let (region, rest) = split_field(&decoded, "region")?;
+    let region = utf8(region, "region")?;
+    partial.region = Some(region.clone());
+
+    let (packet_time, rest) = split_field(rest, "packet time")?;
+    let packet_time = utf8(packet_time, "packet time")?;
+    let packet_time = packet_time
+        .parse::<u64>()
+        .map_err(|_| TokenError::TimeNotNumeric(packet_time))?;
+    i64::try_from(packet_time)
+        .ok()
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .ok_or(TokenError::TimeOutOfRange(packet_time))?;
+    partial.packet_time = Some(packet_time);
+
+    let (route_id, pubkey_bytes) = split_field(rest, "route id")?;
+    let route_id = utf8(route_id, "route id")?;
+    partial.route_id = Some(route_id.clone());
+
+    partial.pubkeybin = Some(pubkey_bytes.to_vec());
+    let pubkey = Pubkey::from_bin(pubkey_bytes)?;
+
+    let b58 = helium_address::to_b58(pubkey_bytes);
+    let animal_name = animal_name(&b58);
+
+    Ok(Token {
         region,
         packet_time,
         route_id,
+        pubkey,
         b58,
         animal_name,
     })
 }
+
+fn split_field<'a>(
+    bytes: &'a [u8],
+    field: &'static str,
+) -> Result<(&'a [u8], &'a [u8]), TokenError> {
+    let at = bytes
+        .windows(2)
+        .position(|w| w == b"::")
+        .ok_or(TokenError::MissingSeparator { field })?;
+    Ok((&bytes[..at], &bytes[at + 2..]))
+}
+
+fn utf8(bytes: &[u8], field: &'static str) -> Result<String, TokenError> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| TokenError::NotUtf8 { field })
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty token"),
+            Self::NotHex(e) => write!(f, "token is not hex: {e}"),
+            Self::MissingSeparator { field } => write!(f, "missing `::` after the {field}"),
+            Self::NotUtf8 { field } => write!(f, "the {field} is not valid UTF-8"),
+            Self::TimeNotNumeric(time) => write!(f, "packet time {time:?} is not a number"),
+            Self::TimeOutOfRange(time) => {
+                write!(
+                    f,
+                    "packet time {time} is too far in the future to be a date"
+                )
+            }
+            Self::PubkeyLength(len) => write!(
+                f,
+                "gateway pubkeybin is {len} bytes, too short to hold a key"
+            ),
+            Self::PubkeyNetwork(network) => write!(
+                f,
+                "gateway network {network} is not mainnet (0) or testnet (1)"
+            ),
+            Self::PubkeyKeyType(key_type) => write!(
+                f,
+                "gateway key type {key_type} is not ecc_compact (0) or ed25519 (1)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_round_trip() {
+        let input = "0x45553836383A3A323638393537393432343A3A31323739363333652D303661302D313165652D393839642D6637316363643537613231383A3A0072C4AE468379170A7F7955A4375C084A8327CAE9ACF0432DC91CFF2E66512257";
+        let token = parse_token(input).unwrap();
+
+        let known = vec![token.b58.clone()];
+        let by_b58 = gateway_pubkeybin(&token.b58, &[]).unwrap();
+        let by_name = gateway_pubkeybin(&token.animal_name.replace('-', " "), &known).unwrap();
+        assert_eq!(by_b58, by_name);
+
+        let encoded = encode_token(&token.region, token.packet_time, &token.route_id, &by_b58);
+        assert_eq!(encoded, input);
+        assert_eq!(parse_token(&encoded).unwrap(), token);
+
+        let time = parse_time("2055-04-01T00:00:00.123Z").unwrap();
+        let encoded = encode_token("US915", time, "route", &by_b58);
+        let parsed = parse_token(&encoded).unwrap();
+        assert_eq!(parsed.region, "US915");
+        assert_eq!(parsed.packet_time, time);
+        assert_eq!(parsed.route_id, "route");
+        assert_eq!(parsed.b58, token.b58);
+
+        assert!(gateway_pubkeybin("not a gateway", &known).is_err());
+    }
+
+    #[test]
+    fn token_errors() {
+        let pubkeybin =
+            gateway_pubkeybin("112qB3YaH5bZkCnKA5uRH7tBtGNv2Y5B4smv1jsmvGUzgKT71QpE", &[]).unwrap();
+
+        let hex = |raw: &[u8]| format!("0x{}", hex::encode(raw));
+        assert_eq!(parse_token(""), Err(TokenError::Empty));
+        assert!(matches!(parse_token("0xZZ"), Err(TokenError::NotHex(_))));
+        assert_eq!(
+            parse_token(&hex(b"US915::123")),
+            Err(TokenError::MissingSeparator {
+                field: "packet time"
+            })
+        );
+        assert_eq!(
+            parse_token(&hex(b"\xFF::1::r::")),
+            Err(TokenError::NotUtf8 { field: "region" })
+        );
+        assert_eq!(
+            parse_token(&hex(b"US915::soon::r::")),
+            Err(TokenError::TimeNotNumeric("soon".to_string()))
+        );
+        assert_eq!(
+            parse_token(&encode_token("US915", u64::MAX, "r", &pubkeybin)),
+            Err(TokenError::TimeOutOfRange(u64::MAX))
+        );
+        assert_eq!(
+            parse_token(&encode_token("US915", 1, "r", &pubkeybin[..1])),
+            Err(TokenError::PubkeyLength(1))
+        );
+        let mut multisig = pubkeybin.clone();
+        multisig[0] = 2;
+        assert_eq!(
+            parse_token(&encode_token("US915", 1, "r", &multisig)),
+            Err(TokenError::PubkeyKeyType(2))
+        );
+        multisig[0] = 0x21;
+        assert_eq!(
+            parse_token(&encode_token("US915", 1, "r", &multisig)),
+            Err(TokenError::PubkeyNetwork(2))
+        );
+
+        let mut partial = PartialToken::default();
+        let _ = read_token(&encode_token("US915", 1, "r", &[]), &mut partial);
+        assert_eq!(partial.region.as_deref(), Some("US915"));
+        assert_eq!(partial.packet_time, Some(1));
+        assert_eq!(partial.route_id.as_deref(), Some("r"));
+        assert_eq!(partial.pubkeybin, Some(vec![]));
+    }
+
+    #[test]
+    fn clock_warnings() {
+        let now = 1_700_000_000_000;
+        assert_eq!(clock_warning(now - 500, now), None);
+        assert_eq!(clock_warning(now + CLOCK_SKEW_MS, now), None);
+        assert!(clock_warning(now + 2 * 60 * 60 * 1000, now)
+            .unwrap()
+            .starts_with("2h in the future"));
+        assert!(clock_warning(1_000, now).unwrap().contains("before 2020"));
+    }
+
+    #[test]
+    fn route_ids() {
+        let canonical = "1279633e-06a0-11ee-989d-f71ccd57a218";
+        assert_eq!(route_key(canonical), canonical);
+        assert_eq!(route_key("1279633E06A011EE989DF71CCD57A218"), canonical);
+        assert_eq!(route_key(" not-a-uuid "), "not-a-uuid");
+
+        let uuid = uuid::Uuid::parse_str(canonical).unwrap();
+        assert_eq!(describe_uuid(&uuid), "v1 (time based)");
+        assert_eq!(describe_uuid(&uuid::Uuid::nil()), "nil UUID");
+    }
+
+    #[test]
+    fn pubkey_type_byte() {
+        let pubkey = Pubkey::from_bin(&[0x01, 0xAA, 0xBB]).unwrap();
+        assert_eq!(pubkey.network, Network::Mainnet);
+        assert_eq!(pubkey.key_type, KeyType::Ed25519);
+        assert_eq!(pubkey.key, [0xAA, 0xBB]);
+        assert!(pubkey.length_mismatch());
+
+        let mut bin = vec![0x10];
+        bin.extend([0x42; 32]);
+        let pubkey = Pubkey::from_bin(&bin).unwrap();
+        assert_eq!(pubkey.network, Network::Testnet);
+        assert_eq!(pubkey.key_type, KeyType::EccCompact);
+        assert!(!pubkey.length_mismatch());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{addressing::DevaddrRange, num_format::ToFormattedString};
+
+/// Lists longer than this are cut short.
+const MAX_LISTED: usize = 32;
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    range: String,
+    other: String,
+    split_prefix_len: u32,
+    merge: String,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            range: Default::default(),
+            other: Default::default(),
+            split_prefix_len: 32,
+            merge: Default::default(),
+        }
+    }
+}
+
+impl State {
+    /// Clicking a NetID the range crosses puts it in `net_id`.
+    pub fn main_view(&mut self, ui: &mut egui::Ui, net_id: &mut String) {
+        let Self {
+            range,
+            other,
+            split_prefix_len,
+            merge,
+        } = self;
+
+        ui.horizontal(|ui| {
+            ui.label("Range:");
+            ui.add(egui::TextEdit::singleline(range).hint_text("48000000/25 or 48000000-4800007F"));
+        });
+        let Some(r) = parse_or_show_error(ui, range) else {
+            return;
+        };
+
+        egui::Grid::new("subnet_grid").show(ui, |ui| {
+            ui.label("Range:");
+            ui.monospace(r.to_string());
+            ui.end_row();
+
+            ui.label("Size:");
+            ui.label(r.size().to_formatted_string());
+            ui.end_row();
+
+            ui.label("Prefix:");
+            match r.prefix_len() {
+                Some(prefix_len) => ui.monospace(format!("{:08X}/{prefix_len}", r.start())),
+                None => ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "Not prefix aligned, covered by:",
+                ),
+            };
+            ui.end_row();
+            if r.prefix_len().is_none() {
+                ui.label("");
+                list_ranges(ui, &r.to_prefixes());
+                ui.end_row();
+            }
+
+            let net_ids: Vec<_> = r.net_ids().take(MAX_LISTED + 1).collect();
+            ui.label("NetIDs:");
+            ui.horizontal_wrapped(|ui| {
+                for n in net_ids.iter().take(MAX_LISTED) {
+                    if ui.button(n.as_hex()).clicked() {
+                        *net_id = n.as_hex();
+                    }
+                }
+                if net_ids.len() > MAX_LISTED {
+                    ui.label(format!("and {} more", r.net_id_count() - MAX_LISTED as u64));
+                }
+                if net_ids.is_empty() {
+                    ui.label("None, Devaddrs starting with FF have no NetID");
+                }
+            });
+            ui.end_row();
+        });
+
+        // ====================================================================
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Compare with:");
+            ui.add(egui::TextEdit::singleline(other).hint_text("48000040/26"));
+        });
+        if let Some(o) = parse_or_show_error(ui, other) {
+            let relation = if r == o {
+                "Same range".to_string()
+            } else if r.contains_range(&o) {
+                format!("{r} contains {o}")
+            } else if o.contains_range(&r) {
+                format!("{o} contains {r}")
+            } else if let Some(both) = r.intersection(&o) {
+                format!(
+                    "Overlap on {both} ({} Devaddrs)",
+                    both.size().to_formatted_string()
+                )
+            } else {
+                "No overlap".to_string()
+            };
+            ui.label(relation);
+        }
+
+        // ====================================================================
+        ui.separator();
+        let min_prefix_len = r.prefix_len().unwrap_or(0);
+        *split_prefix_len = (*split_prefix_len).clamp(min_prefix_len, 32);
+        ui.horizontal(|ui| {
+            ui.label("Split into /");
+            ui.add(egui::DragValue::new(split_prefix_len).clamp_range(min_prefix_len..=32));
+        });
+        if let Ok(pieces) = r.split(*split_prefix_len) {
+            let pieces: Vec<_> = pieces.take(MAX_LISTED + 1).collect();
+            list_ranges(ui, &pieces);
+        }
+
+        // ====================================================================
+        ui.separator();
+        ui.label("Merge (one range per line):");
+        ui.add(
+            egui::TextEdit::multiline(merge)
+                .hint_text("48000000/26\n48000040-4800007F")
+                .desired_rows(3),
+        );
+        let mut ranges: Vec<DevaddrRange> = vec![];
+        for line in merge.lines().filter(|line| !line.trim().is_empty()) {
+            match line.parse() {
+                Ok(range) => ranges.push(range),
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("{line}: {e}"));
+                }
+            }
+        }
+        if !ranges.is_empty() {
+            for merged in DevaddrRange::merge(ranges) {
+                ui.horizontal(|ui| {
+                    ui.monospace(merged.to_string());
+                    ui.label("=");
+                    list_ranges(ui, &merged.to_prefixes());
+                });
+            }
+        }
+    }
+}
+
+fn parse_or_show_error(ui: &mut egui::Ui, input: &str) -> Option<DevaddrRange> {
+    if input.trim().is_empty() {
+        return None;
+    }
+    match input.parse() {
+        Ok(range) => Some(range),
+        Err(e) => {
+            ui.colored_label(ui.visuals().error_fg_color, format!("{e}"));
+            None
+        }
+    }
+}
+
+/// Show `ranges` in prefix notation when they can be, noting if there's more
+/// than [`MAX_LISTED`].
+fn list_ranges(ui: &mut egui::Ui, ranges: &[DevaddrRange]) {
+    ui.horizontal_wrapped(|ui| {
+        for range in ranges.iter().take(MAX_LISTED) {
+            let text = match range.prefix_len() {
+                Some(prefix_len) => format!("{:08X}/{prefix_len}", range.start()),
+                None => range.to_string(),
+            };
+            ui.monospace(text);
+        }
+        if ranges.len() > MAX_LISTED {
+            ui.label("...");
+        }
+    });
+}
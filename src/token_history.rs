@@ -0,0 +1,228 @@
+use crate::roaming_token::{parse_token, route_key, Token};
+use std::collections::BTreeMap;
+
+/// Oldest tokens are dropped past this many.
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct State {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct Entry {
+    token: String,
+    selected: bool,
+}
+
+/// One field across every token being compared.
+#[derive(Debug, PartialEq)]
+struct DiffRow {
+    field: &'static str,
+    values: Vec<String>,
+    /// Not every token has the same value.
+    differs: bool,
+}
+
+impl State {
+    /// Keep `token`, unless the same bytes are already in the history.
+    pub fn record(&mut self, token: &str) {
+        let token = token.trim();
+        let bytes = token_bytes(token);
+        if self.entries.iter().any(|e| token_bytes(&e.token) == bytes) {
+            return;
+        }
+        self.entries.push(Entry {
+            token: token.to_string(),
+            selected: false,
+        });
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Loading an entry puts it in `input_token`.
+    pub fn main_view(
+        &mut self,
+        ui: &mut egui::Ui,
+        input_token: &mut String,
+        route_labels: &BTreeMap<String, String>,
+    ) {
+        let Self { entries } = self;
+
+        if entries.is_empty() {
+            ui.weak("Tokens show up here after leaving the token field or saving them.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} tokens, select two or more to compare",
+                entries.len()
+            ));
+            if ui.button("Clear").clicked() {
+                entries.clear();
+            }
+        });
+
+        let mut remove = None;
+        egui::Grid::new("token_history")
+            .striped(true)
+            .show(ui, |ui| {
+                for (idx, entry) in entries.iter_mut().enumerate() {
+                    ui.checkbox(&mut entry.selected, "");
+                    match parse_token(&entry.token) {
+                        Ok(token) => {
+                            ui.label(&token.region);
+                            ui.label(token.packet_time.to_string());
+                            ui.label(&token.animal_name);
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                            ui.label("");
+                            ui.label("");
+                        }
+                    }
+                    if ui.button("Load").clicked() {
+                        input_token.clone_from(&entry.token);
+                    }
+                    if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                        remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(idx) = remove {
+            entries.remove(idx);
+        }
+
+        let selected: Vec<Token> = entries
+            .iter()
+            .filter(|e| e.selected)
+            .filter_map(|e| parse_token(&e.token).ok())
+            .collect();
+        if selected.len() < 2 {
+            return;
+        }
+
+        ui.separator();
+        egui::Grid::new("token_diff").striped(true).show(ui, |ui| {
+            for row in diff(&selected, route_labels) {
+                ui.strong(row.field);
+                for value in row.values {
+                    if row.differs {
+                        ui.colored_label(ui.visuals().warn_fg_color, value);
+                    } else {
+                        ui.label(value);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// The bytes a token decodes to, ignoring the `0x` prefix and case. Falls
+/// back to the text for anything that isn't hex.
+fn token_bytes(token: &str) -> Vec<u8> {
+    let token = token.trim();
+    let hex = token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+        .unwrap_or(token);
+    hex::decode(hex).unwrap_or_else(|_| token.as_bytes().to_vec())
+}
+
+/// Compare every token's fields, with packet time deltas from the first.
+fn diff(tokens: &[Token], route_labels: &BTreeMap<String, String>) -> Vec<DiffRow> {
+    let row = |field, values: Vec<String>| DiffRow {
+        field,
+        differs: values.iter().any(|v| *v != values[0]),
+        values,
+    };
+    let first_time = tokens[0].packet_time as i128;
+
+    vec![
+        row("Region", tokens.iter().map(|t| t.region.clone()).collect()),
+        row(
+            "Packet Time",
+            tokens.iter().map(|t| t.packet_time.to_string()).collect(),
+        ),
+        DiffRow {
+            field: "Delta (ms)",
+            values: tokens
+                .iter()
+                .map(|t| format!("{:+}", t.packet_time as i128 - first_time))
+                .collect(),
+            differs: false,
+        },
+        row(
+            "Route ID",
+            tokens
+                .iter()
+                .map(|t| {
+                    route_labels
+                        .get(&route_key(&t.route_id))
+                        .cloned()
+                        .unwrap_or_else(|| t.route_id.clone())
+                })
+                .collect(),
+        ),
+        row(
+            "Gateway",
+            tokens.iter().map(|t| t.animal_name.clone()).collect(),
+        ),
+        row(
+            "Network",
+            tokens
+                .iter()
+                .map(|t| t.pubkey.network.to_string())
+                .collect(),
+        ),
+        row(
+            "Key Type",
+            tokens
+                .iter()
+                .map(|t| t.pubkey.key_type.to_string())
+                .collect(),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roaming_token::encode_token;
+
+    #[test]
+    fn diff_tokens() {
+        let mut bin = vec![0x01];
+        bin.extend([0x42; 32]);
+        let a = parse_token(&encode_token("US915", 1_000, "route", &bin)).unwrap();
+        let b = parse_token(&encode_token("US915", 1_250, "route", &bin)).unwrap();
+        bin[1] = 0;
+        let c = parse_token(&encode_token("US915", 900, "route", &bin)).unwrap();
+
+        let labels = BTreeMap::from([("route".to_string(), "Acme".to_string())]);
+        let rows = diff(&[a, b, c], &labels);
+        let find = |field| rows.iter().find(|r| r.field == field).unwrap();
+
+        assert!(!find("Region").differs);
+        assert!(find("Packet Time").differs);
+        assert_eq!(find("Delta (ms)").values, ["+0", "+250", "-100"]);
+        assert_eq!(find("Route ID").values, ["Acme", "Acme", "Acme"]);
+        assert!(find("Gateway").differs);
+    }
+
+    #[test]
+    fn record_dedupes() {
+        let mut state = State::default();
+        state.record("0x0a");
+        state.record(" 0x0a ");
+        state.record("0A");
+        state.record("0X0A");
+        state.record("0x02");
+        assert_eq!(state.entries.len(), 2);
+    }
+}